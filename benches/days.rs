@@ -1,43 +1,69 @@
+use std::env;
+
 use criterion::{criterion_group, criterion_main, Criterion};
-use aoc::solutions::*;
-use aoc::read_file;
-
-macro_rules! bench_day {
-    ($c: expr, $day:path, $day_num:literal) => {{
-        use $day::*;
-        let input = read_file("inputs", $day_num);
-
-        $c.bench_function(&format!("Day {} - Part 1", $day_num), |b| b.iter(|| {
-            part_one(&input)
-        }));
-
-        $c.bench_function(&format!("Day {} - Part 2", $day_num), |b| b.iter(|| {
-            part_two(&input)
-        }));
-    }};
+use aoc::cli::Days;
+use aoc::{read_file, Parsed, Solution};
+
+/// Restricts the benchmarks to some days, e.g. `AOC_BENCH_DAYS=20..=22`.
+const DAYS_VAR: &str = "AOC_BENCH_DAYS";
+
+/// The days to benchmark, all registered days unless `AOC_BENCH_DAYS` is set.
+///
+/// Every selected day has to have its puzzle input, a missing one fails the
+/// run instead of quietly leaving the day out.
+fn selected_days() -> Vec<&'static dyn Solution> {
+    let days = match env::var(DAYS_VAR) {
+        Ok(days) => days.parse().unwrap_or_else(|err| panic!("{}: {}", DAYS_VAR, err)),
+        Err(_) => Days::All,
+    };
+
+    days.select().unwrap_or_else(|err| panic!("{}: {}", DAYS_VAR, err))
+}
+
+fn name(solution: &dyn Solution) -> String {
+    format!("day{:02}", solution.day())
 }
 
 pub fn criterion_benchmark(c: &mut Criterion) {
-    bench_day!(c, day01, 1);
-    bench_day!(c, day02, 2);
-    bench_day!(c, day03, 3);
-    bench_day!(c, day04, 4);
-    bench_day!(c, day05, 5);
-    bench_day!(c, day06, 6);
-    bench_day!(c, day07, 7);
-    bench_day!(c, day08, 8);
-    bench_day!(c, day09, 9);
-    bench_day!(c, day10, 10);
-    bench_day!(c, day11, 11);
-    bench_day!(c, day12, 12);
-    bench_day!(c, day13, 13);
-    bench_day!(c, day14, 14);
-    bench_day!(c, day15, 15);
-    bench_day!(c, day16, 16);
-    bench_day!(c, day17, 17);
-    bench_day!(c, day18, 18);
-    bench_day!(c, day19, 19);
+    let days: Vec<_> = selected_days()
+        .into_iter()
+        .map(|solution| (solution, read_file("inputs", solution.day())))
+        .collect();
+
+    let mut group = c.benchmark_group("parse");
+
+    for (solution, input) in &days {
+        group.bench_function(name(*solution), |b| b.iter(|| solution.parse(input)));
+    }
+
+    group.finish();
+
+    let parsed: Vec<(&dyn Solution, Parsed)> = days
+        .iter()
+        .map(|(solution, input)| {
+            let parsed = solution
+                .parse(input)
+                .unwrap_or_else(|err| panic!("day {}: {}", solution.day(), err));
+            (*solution, parsed)
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("part 1");
+
+    for (solution, input) in &parsed {
+        group.bench_function(name(*solution), |b| b.iter(|| solution.part_one(input)));
+    }
+
+    group.finish();
+
+    let mut group = c.benchmark_group("part 2");
+
+    for (solution, input) in &parsed {
+        group.bench_function(name(*solution), |b| b.iter(|| solution.part_two(input)));
+    }
+
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
@@ -0,0 +1,123 @@
+use std::fmt;
+
+/// The answer to one part of a day.
+///
+/// Days compute their answers in whatever integer type fits the puzzle, so
+/// every part converts its result into an `Answer` instead. Integers are stored
+/// widened to 128 bit, which keeps every conversion lossless: non-negative
+/// values always end up as `Unsigned`, `Signed` only holds negative ones.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Unsigned(u128),
+    Signed(i128),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Unsigned(n) => n.fmt(f),
+            Answer::Signed(n) => n.fmt(f),
+            Answer::Text(s) => s.fmt(f),
+        }
+    }
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Answer::Unsigned(n as u128)
+            }
+        }
+
+        impl PartialEq<$t> for Answer {
+            fn eq(&self, other: &$t) -> bool {
+                matches!(self, Answer::Unsigned(n) if *n == *other as u128)
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                if n < 0 {
+                    Answer::Signed(n as i128)
+                } else {
+                    Answer::Unsigned(n as u128)
+                }
+            }
+        }
+
+        impl PartialEq<$t> for Answer {
+            fn eq(&self, other: &$t) -> bool {
+                match self {
+                    Answer::Unsigned(n) => *other >= 0 && *n == *other as u128,
+                    Answer::Signed(n) => *n == *other as i128,
+                    Answer::Text(_) => false,
+                }
+            }
+        }
+    )*};
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_signed!(i8, i16, i32, i64, i128, isize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+impl PartialEq<str> for Answer {
+    fn eq(&self, other: &str) -> bool {
+        matches!(self, Answer::Text(s) if s == other)
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion() {
+        assert_eq!(Answer::from(6599u16), Answer::Unsigned(6599));
+        assert_eq!(Answer::from(908i64), Answer::Unsigned(908));
+        assert_eq!(Answer::from(-3i64), Answer::Signed(-3));
+        assert_eq!(Answer::from(u128::MAX), Answer::Unsigned(u128::MAX));
+        assert_eq!(Answer::from(i128::MIN), Answer::Signed(i128::MIN));
+        assert_eq!(Answer::from("abc"), Answer::Text("abc".to_string()));
+    }
+
+    #[test]
+    fn test_literals() {
+        assert_eq!(Answer::from(54968u32), 54968);
+        assert_eq!(Answer::from(952408144115i64), 952408144115u64);
+        assert_eq!(Answer::from(-1i32), -1);
+        assert_eq!(Answer::from("abc"), "abc");
+        assert_ne!(Answer::from(1u8), 2);
+        assert_ne!(Answer::from("1"), 1);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(167409079868000u128).to_string(), "167409079868000");
+        assert_eq!(Answer::from(-42i64).to_string(), "-42");
+        assert_eq!(Answer::from("abc").to_string(), "abc");
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{parse, Answer, ParseError};
+
+/// The accepted answers of the puzzle inputs, stored in `answers.toml`.
+///
+/// The file uses a small subset of TOML, one table per day with a key per
+/// part. Numbers are written as integers and text answers as strings:
+///
+/// ```toml
+/// [day01]
+/// part1 = 54968
+/// part2 = 54094
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(u8, u8), Answer>,
+}
+
+/// How a computed answer compares to the accepted one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Match,
+    Mismatch(Answer),
+    Unknown,
+}
+
+/// `answers.toml` in the root of the crate.
+pub fn default_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("answers.toml")
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut answers = Self::default();
+        let mut day = None;
+
+        for line in input.lines() {
+            let content = line.trim();
+
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+
+            if let Some(header) = content.strip_prefix('[') {
+                let (name, rest) = parse::split_once(input, header, "]")?;
+                expect_end(input, rest)?;
+
+                day = match name.strip_prefix("day").map(str::parse) {
+                    Some(Ok(n @ 1..=25)) => Some(n),
+                    _ => return Err(ParseError::at(input, name, "expected a table like `day01`")),
+                };
+
+                continue;
+            }
+
+            let (key, value) = parse::split_once(input, content, "=")?;
+            let key = key.trim();
+
+            let part = match key {
+                "part1" => 1,
+                "part2" => 2,
+                _ => return Err(ParseError::at(input, key, "expected `part1` or `part2`")),
+            };
+
+            let day = day.ok_or_else(|| ParseError::at(input, key, "answer outside of a day table"))?;
+            let answer = parse_value(input, value.trim())?;
+
+            if answers.insert(day, part, answer).is_some() {
+                return Err(ParseError::at(input, key, "duplicate answer"));
+            }
+        }
+
+        Ok(answers)
+    }
+
+    /// Loads answers from `path`, a missing file counts as no answers yet.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let input = match fs::read_to_string(path) {
+            Ok(input) => input,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err),
+        };
+
+        Self::parse(&input).map_err(|err| {
+            let message = format!("{}\n{}", path.display(), err.render(&input));
+            io::Error::new(io::ErrorKind::InvalidData, message)
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+
+    /// Stores an answer, returning the one it replaced.
+    pub fn insert(&mut self, day: u8, part: u8, answer: Answer) -> Option<Answer> {
+        self.answers.insert((day, part), answer)
+    }
+
+    pub fn check(&self, day: u8, part: u8, answer: &Answer) -> Verdict {
+        match self.get(day, part) {
+            Some(expected) if expected == answer => Verdict::Match,
+            Some(expected) => Verdict::Mismatch(expected.clone()),
+            None => Verdict::Unknown,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+}
+
+fn expect_end(input: &str, rest: &str) -> Result<(), ParseError> {
+    let rest = rest.trim_start();
+
+    if rest.is_empty() || rest.starts_with('#') {
+        Ok(())
+    } else {
+        Err(ParseError::at(input, rest, "unexpected text after"))
+    }
+}
+
+fn parse_value(input: &str, value: &str) -> Result<Answer, ParseError> {
+    let Some(quoted) = value.strip_prefix('"') else {
+        let (number, comment) = value.split_at(value.find('#').unwrap_or(value.len()));
+        let number = number.trim_end();
+
+        expect_end(input, comment)?;
+
+        return match number.strip_prefix('-') {
+            Some(_) => parse::number::<i128>(input, number).map(Answer::from),
+            None => parse::number::<u128>(input, number).map(Answer::from),
+        };
+    };
+
+    let mut text = String::new();
+    let mut chars = quoted.char_indices();
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' => {
+                expect_end(input, &quoted[idx + 1..])?;
+                return Ok(Answer::Text(text));
+            }
+            '\\' => match chars.next() {
+                Some((_, '"')) => text.push('"'),
+                Some((_, '\\')) => text.push('\\'),
+                Some((_, 'n')) => text.push('\n'),
+                _ => return Err(ParseError::at(input, parse::char_at(quoted, idx), "invalid escape")),
+            },
+            c => text.push(c),
+        }
+    }
+
+    Err(ParseError::at(input, value, "unterminated string"))
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut current = None;
+
+        for (&(day, part), answer) in &self.answers {
+            if current != Some(day) {
+                if current.is_some() {
+                    writeln!(f)?;
+                }
+
+                writeln!(f, "[day{:02}]", day)?;
+                current = Some(day);
+            }
+
+            match answer {
+                Answer::Text(text) => {
+                    let escaped = text
+                        .replace('\\', "\\\\")
+                        .replace('"', "\\\"")
+                        .replace('\n', "\\n");
+                    writeln!(f, "part{} = \"{}\"", part, escaped)?;
+                }
+                answer => writeln!(f, "part{} = {}", part, answer)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let input = "# accepted answers\n\n[day01]\npart1 = 54968\npart2 = -3 # negative\n\n[day13]\npart1 = \"A\\\"B\"\n";
+        let answers = Answers::parse(input).unwrap();
+
+        assert_eq!(answers.len(), 3);
+        assert_eq!(answers.get(1, 1), Some(&Answer::from(54968u32)));
+        assert_eq!(answers.get(1, 2), Some(&Answer::from(-3)));
+        assert_eq!(answers.get(13, 1), Some(&Answer::from("A\"B")));
+        assert_eq!(answers.get(13, 2), None);
+
+        assert_eq!(Answers::parse(&answers.to_string()).unwrap(), answers);
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = Answers::parse("part1 = 3").unwrap_err();
+        assert_eq!(err.message, "answer outside of a day table");
+
+        let err = Answers::parse("[day01]\npart1 = 3\npart1 = 4").unwrap_err();
+        assert_eq!((err.line, err.message.as_str()), (3, "duplicate answer"));
+
+        let err = Answers::parse("[day01]\npart3 = 3").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+
+        let err = Answers::parse("[day01]\npart1 = 3x").unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 9: invalid number `3x`");
+
+        assert!(Answers::parse("[day26]").is_err());
+        assert!(Answers::parse("[day01]\npart1 = \"abc").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::parse("[day07]\npart1 = 6440").unwrap();
+
+        assert_eq!(answers.check(7, 1, &6440u32.into()), Verdict::Match);
+        assert_eq!(answers.check(7, 1, &6441u32.into()), Verdict::Mismatch(6440u32.into()));
+        assert_eq!(answers.check(7, 2, &5905u32.into()), Verdict::Unknown);
+    }
+
+    #[test]
+    fn test_repository_answers() {
+        let answers = Answers::load(&default_path()).unwrap();
+        assert!(!answers.is_empty());
+    }
+}
@@ -0,0 +1,286 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{parse, ParseError};
+
+/// The steps of a day that are measured, in the order they run.
+pub const STEPS: [&str; 3] = ["parse", "part 1", "part 2"];
+
+const HEADER: &str = "day,parse_ns,part1_ns,part2_ns";
+
+/// Stored timings of every step of some days, saved by `aoc bench --save`.
+///
+/// Baselines are small CSV files with one line per day and the times in
+/// nanoseconds:
+///
+/// ```text
+/// day,parse_ns,part1_ns,part2_ns
+/// 1,438890,636350,9090000
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Baseline {
+    days: BTreeMap<u8, [Duration; 3]>,
+}
+
+/// The folder baselines are stored in, `.bench` in the root of the crate.
+pub fn dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(".bench")
+}
+
+/// The file of the baseline called `name`.
+///
+/// Names are used as file names, so they may only contain letters, digits,
+/// `-`, `_` and `.`.
+pub fn path(name: &str) -> Option<PathBuf> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+    valid.then(|| dir().join(format!("{}.csv", name)))
+}
+
+impl Baseline {
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut baseline = Self::default();
+        let mut lines = input.lines();
+
+        match lines.next() {
+            Some(HEADER) => {}
+            Some(line) => return Err(ParseError::at(input, line, format!("expected `{}`", HEADER))),
+            None => return Err(ParseError::end_of_input(input, "empty baseline")),
+        }
+
+        for line in lines.filter(|line| !line.trim().is_empty()) {
+            let mut fields = line.split(',');
+            let day = parse::number(input, fields.next().unwrap())?;
+            let mut steps = [Duration::ZERO; 3];
+
+            for step in &mut steps {
+                let field = fields
+                    .next()
+                    .ok_or_else(|| ParseError::at(input, line, "expected 4 columns"))?;
+                *step = Duration::from_nanos(parse::number(input, field)?);
+            }
+
+            if let Some(field) = fields.next() {
+                return Err(ParseError::at(input, field, "unexpected column"));
+            }
+
+            if baseline.insert(day, steps).is_some() {
+                return Err(ParseError::at(input, line, "duplicate day"));
+            }
+        }
+
+        Ok(baseline)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let input = fs::read_to_string(path)?;
+
+        Self::parse(&input).map_err(|err| {
+            let message = format!("{}\n{}", path.display(), err.render(&input));
+            io::Error::new(io::ErrorKind::InvalidData, message)
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(path, self.to_string())
+    }
+
+    /// Stores the timings of `day`, returning the ones they replaced.
+    pub fn insert(&mut self, day: u8, steps: [Duration; 3]) -> Option<[Duration; 3]> {
+        self.days.insert(day, steps)
+    }
+
+    pub fn get(&self, day: u8) -> Option<&[Duration; 3]> {
+        self.days.get(&day)
+    }
+
+    /// Compares `current` against this baseline, for the days in both.
+    pub fn compare(&self, current: &Baseline) -> Comparison {
+        let mut changes = Vec::new();
+
+        for (&day, steps) in &current.days {
+            let Some(baseline) = self.get(day) else {
+                continue;
+            };
+
+            for (step, (&before, &after)) in baseline.iter().zip(steps).enumerate() {
+                changes.push(Change {
+                    day,
+                    step,
+                    before,
+                    after,
+                });
+            }
+        }
+
+        Comparison { changes }
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+
+        for (day, [parse, one, two]) in &self.days {
+            writeln!(
+                f,
+                "{},{},{},{}",
+                day,
+                parse.as_nanos(),
+                one.as_nanos(),
+                two.as_nanos()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How the time of one step of a day changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub day: u8,
+    /// Index into [`STEPS`].
+    pub step: usize,
+    pub before: Duration,
+    pub after: Duration,
+}
+
+impl Change {
+    /// The change in percent, positive when the step got slower.
+    pub fn percent(&self) -> f64 {
+        let before = self.before.as_secs_f64();
+
+        if before == 0.0 {
+            0.0
+        } else {
+            (self.after.as_secs_f64() / before - 1.0) * 100.0
+        }
+    }
+
+    /// Whether the step got slower by more than `threshold` percent.
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.percent() > threshold
+    }
+}
+
+/// The changes of every step between a baseline and a new measurement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    changes: Vec<Change>,
+}
+
+impl Comparison {
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn regressions(&self, threshold: f64) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(move |change| change.is_regression(threshold))
+    }
+}
+
+fn speed(change: &Change) -> String {
+    let (before, after) = (change.before.as_secs_f64(), change.after.as_secs_f64());
+
+    if before == 0.0 || after == 0.0 {
+        "-".to_string()
+    } else if after <= before {
+        format!("{:.2}x faster", before / after)
+    } else {
+        format!("{:.2}x slower", after / before)
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>3}  {:<6}  {:>10}  {:>10}  {:>8}  Speed",
+            "Day", "Step", "Before", "After", "Change"
+        )?;
+
+        for change in &self.changes {
+            write!(
+                f,
+                "\n{:>3}  {:<6}  {:>10}  {:>10}  {:>+7.1}%  {}",
+                change.day,
+                STEPS[change.step],
+                format!("{:.2?}", change.before),
+                format!("{:.2?}", change.after),
+                change.percent(),
+                speed(change)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_parse() {
+        let baseline = Baseline::parse("day,parse_ns,part1_ns,part2_ns\n1,10,20,30\n14,1,2,3\n").unwrap();
+        assert_eq!(baseline.get(14), Some(&[1, 2, 3].map(Duration::from_nanos)));
+        assert_eq!(Baseline::parse(&baseline.to_string()), Ok(baseline));
+
+        assert!(Baseline::parse("").is_err());
+        assert!(Baseline::parse("day,ns\n").is_err());
+        assert!(Baseline::parse("day,parse_ns,part1_ns,part2_ns\n1,10,20").is_err());
+        assert!(Baseline::parse("day,parse_ns,part1_ns,part2_ns\n1,10,20,30,40").is_err());
+        assert!(Baseline::parse("day,parse_ns,part1_ns,part2_ns\n1,1,2,3\n1,1,2,3").is_err());
+    }
+
+    #[test]
+    fn test_path() {
+        assert_eq!(path("before-simd"), Some(dir().join("before-simd.csv")));
+        assert_eq!(path("../answers"), None);
+        assert_eq!(path(".hidden"), None);
+        assert_eq!(path(""), None);
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut before = Baseline::default();
+        before.insert(1, [ms(10), ms(20), ms(40)]);
+        before.insert(2, [ms(1), ms(1), ms(1)]);
+
+        let mut after = Baseline::default();
+        after.insert(1, [ms(10), ms(10), ms(50)]);
+        after.insert(3, [ms(1), ms(1), ms(1)]);
+
+        let comparison = before.compare(&after);
+        let percent: Vec<_> = comparison.changes().iter().map(Change::percent).collect();
+        assert_eq!(percent, [0.0, -50.0, 25.0]);
+
+        let regressions: Vec<_> = comparison.regressions(10.0).map(|change| change.step).collect();
+        assert_eq!(regressions, [2]);
+        assert_eq!(comparison.regressions(30.0).count(), 0);
+
+        let table = comparison.to_string();
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines[2], "  1  part 1     20.00ms     10.00ms    -50.0%  2.00x faster");
+        assert_eq!(lines[3], "  1  part 2     40.00ms     50.00ms    +25.0%  1.25x slower");
+    }
+}
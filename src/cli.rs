@@ -0,0 +1,926 @@
+use std::{fmt, ops::RangeInclusive, path::PathBuf, str::FromStr, time::Duration};
+
+use crate::solutions::{day17::CrucibleRules, SOLUTIONS};
+use crate::{baseline, Solution};
+
+pub const USAGE: &str = "\
+Usage:
+    aoc run <days> [--part 1|2] [--input <path> | --example]
+    aoc run --all [--part 1|2] [--example] [--timings] [--budget <time>]
+    aoc run <days> --format json|csv [--output <path>]
+    aoc run --all --jobs <n>
+    aoc fetch <days> [--force]
+    aoc verify [<days>] [--record] [--answers <path>]
+    aoc new <day> [--title <title>]
+    aoc bench [<days>] [--runs <n>] [--save <name>] [--compare <name>]
+              [--threshold <percent>]
+    aoc crucible [--min-straight <n>] [--max-straight <n>] [--allow-reverse]
+                 [--input <path> | --example] [--route]
+    aoc trace 20 [--presses <n>] [--input <path> | --example]
+                 [--output <path>]
+    aoc graph 20 [--input <path> | --example] [--output <path>]
+    aoc list
+    aoc help
+
+<days> is a single day (`3`), an inclusive range (`3..=9`) or an
+exclusive range (`3..10`). `aoc <day>` is short for `aoc run <day>`.
+
+Options:
+    --part <1|2>      only run the given part
+    --input <path>    read the puzzle input from <path>
+    --example         read examples/dayNN/example.txt instead of the puzzle
+                      input
+    --timings         print a table of the runtimes instead of the answers
+    --budget <time>   fail if the total runtime exceeds <time>, e.g. `1s` or
+                      `250ms`, implies --timings
+    --format <format> print the results as `text` (the default), `json` or
+                      `csv`, one record per part
+    --output <path>   write the json or csv records to <path> instead of
+                      stdout; for `trace` and `graph`, the file to write
+                      (default day20-trace.txt and day20.dot)
+    --jobs <n>        solve up to <n> days at the same time, the results are
+                      still printed in order; the default of 1 runs the days
+                      one after another, which keeps the timings accurate
+    --force           download the input again even if it is cached
+    --record          store the answers that are not known yet
+    --answers <path>  read the accepted answers from <path>
+    --title <title>   the puzzle title of a new day
+    --runs <n>        how often `bench` runs every step, the median is kept
+                      (default 5)
+    --save <name>     store the bench timings as the baseline <name>
+    --compare <name>  compare the bench timings with the baseline <name>
+    --threshold <percent>
+                      fail if a step got more than <percent> slower than the
+                      baseline (default 10)
+    --min-straight <n>
+                      the blocks a crucible moves straight before it can
+                      turn or stop (default 1)
+    --max-straight <n>
+                      the blocks a crucible moves straight at most
+                      (default 3)
+    --allow-reverse   let the crucible turn around
+    --route           draw the route of the crucible onto the map
+    --presses <n>     how often `trace` pushes the button (default 1)
+
+`fetch` downloads puzzle inputs into the inputs folder, using the session
+cookie from the AOC_SESSION environment variable. `verify` runs the days
+(all of them by default) and compares the answers with answers.toml.
+`new` creates the module, the input placeholders and the registry entry
+for a day that is not solved yet. `bench` measures the days (all of them by
+default) on their puzzle inputs, baselines are kept in the .bench folder.
+`crucible` finds the route with the least heat loss of day 17 for any
+crucible, `--min-straight 4 --max-straight 10` is the ultra crucible.
+`trace` writes every pulse sent while pushing the button of day 20,
+`graph` writes its circuit as a Graphviz graph.";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Run(RunOptions),
+    Fetch(FetchOptions),
+    Verify(VerifyOptions),
+    New(NewOptions),
+    Bench(BenchOptions),
+    Crucible(CrucibleOptions),
+    Trace(TraceOptions),
+    Graph(GraphOptions),
+    List,
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunOptions {
+    pub days: Days,
+    pub part: Option<Part>,
+    pub input: InputSource,
+    pub timings: bool,
+    pub budget: Option<Duration>,
+    pub format: Format,
+    pub output: Option<PathBuf>,
+    pub jobs: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchOptions {
+    pub days: Days,
+    pub force: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyOptions {
+    pub days: Days,
+    pub record: bool,
+    pub answers: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewOptions {
+    pub day: u8,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchOptions {
+    pub days: Days,
+    pub runs: usize,
+    pub save: Option<String>,
+    pub compare: Option<String>,
+    /// The allowed slowdown against the baseline, in percent.
+    pub threshold: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrucibleOptions {
+    pub rules: CrucibleRules,
+    pub input: InputSource,
+    pub route: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceOptions {
+    pub presses: usize,
+    pub input: InputSource,
+    pub output: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphOptions {
+    pub input: InputSource,
+    pub output: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Days {
+    All,
+    Range(RangeInclusive<u8>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Puzzle,
+    Example,
+    Path(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliError(pub String);
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for CliError {}
+
+macro_rules! cli_error {
+    ($($arg:tt)*) => {
+        CliError(format!($($arg)*))
+    };
+}
+
+impl Part {
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl Days {
+    /// All selected day numbers, whether they are solved or not.
+    pub fn numbers(&self) -> RangeInclusive<u8> {
+        match self {
+            Days::All => 1..=25,
+            Days::Range(range) => range.clone(),
+        }
+    }
+
+    /// Looks up the selected days in the registry.
+    ///
+    /// A single day that is not solved is an error, ranges just skip the
+    /// unsolved days as long as at least one of them is solved.
+    pub fn select(&self) -> Result<Vec<&'static dyn Solution>, CliError> {
+        let range = match self {
+            Days::All => return Ok(SOLUTIONS.to_vec()),
+            Days::Range(range) => range,
+        };
+
+        let selected: Vec<_> = SOLUTIONS
+            .iter()
+            .copied()
+            .filter(|solution| range.contains(&solution.day()))
+            .collect();
+
+        if selected.is_empty() {
+            let what = if range.start() == range.end() {
+                format!("day {} is", range.start())
+            } else {
+                format!("none of the days {}..={} are", range.start(), range.end())
+            };
+
+            return Err(cli_error!("{} not solved yet, solved days are {}", what, solved_days()));
+        }
+
+        Ok(selected)
+    }
+}
+
+fn solved_days() -> String {
+    let mut ranges: Vec<(u8, u8)> = Vec::new();
+
+    for solution in SOLUTIONS {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == solution.day() => *end = solution.day(),
+            _ => ranges.push((solution.day(), solution.day())),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}..={}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn parse_day(s: &str) -> Result<u8, CliError> {
+    match s.parse::<u8>() {
+        Ok(day @ 1..=25) => Ok(day),
+        _ => Err(cli_error!("invalid day `{}`, expected a number from 1 to 25", s)),
+    }
+}
+
+fn parse_days(s: &str) -> Result<Days, CliError> {
+    let range = if let Some((start, end)) = s.split_once("..=") {
+        parse_day(start)?..=parse_day(end)?
+    } else if let Some((start, end)) = s.split_once("..") {
+        let (start, end) = (parse_day(start)?, parse_day(end)?);
+
+        if end <= start {
+            return Err(cli_error!("empty day range `{}`", s));
+        }
+
+        start..=end - 1
+    } else {
+        let day = parse_day(s)?;
+        day..=day
+    };
+
+    if range.is_empty() {
+        return Err(cli_error!("empty day range `{}`", s));
+    }
+
+    Ok(Days::Range(range))
+}
+
+impl FromStr for Days {
+    type Err = CliError;
+
+    /// Parses a day or a range like on the command line, `all` selects every
+    /// day.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Days::All),
+            _ => parse_days(s),
+        }
+    }
+}
+
+fn parse_part(s: &str) -> Result<Part, CliError> {
+    match s {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(cli_error!("invalid part `{}`, expected 1 or 2", s)),
+    }
+}
+
+fn parse_format(s: &str) -> Result<Format, CliError> {
+    match s {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        "csv" => Ok(Format::Csv),
+        _ => Err(cli_error!("invalid format `{}`, expected text, json or csv", s)),
+    }
+}
+
+fn parse_count(what: &str, s: &str) -> Result<usize, CliError> {
+    match s.parse::<usize>() {
+        Ok(count) if count > 0 => Ok(count),
+        _ => Err(cli_error!("invalid number of {} `{}`, expected a positive number", what, s)),
+    }
+}
+
+fn parse_duration(s: &str) -> Result<Duration, CliError> {
+    let split = s.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+
+    let scale = match unit {
+        "s" => 1.0,
+        "ms" => 1e-3,
+        "us" | "µs" => 1e-6,
+        _ => return Err(cli_error!("invalid duration `{}`, expected a unit of s, ms or us", s)),
+    };
+
+    match value.parse::<f64>() {
+        Ok(value) if value > 0.0 => Ok(Duration::from_secs_f64(value * scale)),
+        _ => Err(cli_error!("invalid duration `{}`", s)),
+    }
+}
+
+fn set_input(input: &mut InputSource, source: InputSource) -> Result<(), CliError> {
+    if *input != InputSource::Puzzle {
+        return Err(cli_error!("--input and --example cannot be combined"));
+    }
+
+    *input = source;
+    Ok(())
+}
+
+fn parse_run(mut args: impl Iterator<Item = String>) -> Result<RunOptions, CliError> {
+    let mut days = None;
+    let mut part = None;
+    let mut input = InputSource::Puzzle;
+    let mut timings = false;
+    let mut budget = None;
+    let mut format = Format::Text;
+    let mut output = None;
+    let mut jobs = 1;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| cli_error!("missing value for {}", name))
+        };
+
+        match arg.as_str() {
+            "--all" => days = Some(Days::All),
+            "--part" => part = Some(parse_part(&value("--part")?)?),
+            "--input" => set_input(&mut input, InputSource::Path(value("--input")?.into()))?,
+            "--example" => set_input(&mut input, InputSource::Example)?,
+            "--timings" => timings = true,
+            "--budget" => {
+                budget = Some(parse_duration(&value("--budget")?)?);
+                timings = true;
+            }
+            "--format" => format = parse_format(&value("--format")?)?,
+            "--output" => output = Some(value("--output")?.into()),
+            "--jobs" => jobs = parse_count("jobs", &value("--jobs")?)?,
+            flag if flag.starts_with('-') => return Err(cli_error!("unknown option `{}`", flag)),
+            _ if days.is_some() => return Err(cli_error!("unexpected argument `{}`", arg)),
+            _ => days = Some(parse_days(&arg)?),
+        }
+    }
+
+    let days = days.ok_or_else(|| cli_error!("missing day, pass a day, a range or --all"))?;
+
+    if let InputSource::Path(_) = input {
+        if !matches!(&days, Days::Range(range) if range.start() == range.end()) {
+            return Err(cli_error!("--input can only be used with a single day"));
+        }
+    }
+
+    if format != Format::Text && timings {
+        return Err(cli_error!("--timings and --budget cannot be combined with --format"));
+    }
+
+    if format == Format::Text && output.is_some() {
+        return Err(cli_error!("--output needs --format json or --format csv"));
+    }
+
+    Ok(RunOptions {
+        days,
+        part,
+        input,
+        timings,
+        budget,
+        format,
+        output,
+        jobs,
+    })
+}
+
+fn parse_fetch(args: impl Iterator<Item = String>) -> Result<FetchOptions, CliError> {
+    let mut days = None;
+    let mut force = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--all" => days = Some(Days::All),
+            "--force" => force = true,
+            flag if flag.starts_with('-') => return Err(cli_error!("unknown option `{}`", flag)),
+            _ if days.is_some() => return Err(cli_error!("unexpected argument `{}`", arg)),
+            _ => days = Some(parse_days(&arg)?),
+        }
+    }
+
+    let days = days.ok_or_else(|| cli_error!("missing day, pass a day, a range or --all"))?;
+    Ok(FetchOptions { days, force })
+}
+
+fn parse_verify(mut args: impl Iterator<Item = String>) -> Result<VerifyOptions, CliError> {
+    let mut days = None;
+    let mut record = false;
+    let mut answers = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => days = Some(Days::All),
+            "--record" => record = true,
+            "--answers" => {
+                let path = args.next().ok_or_else(|| cli_error!("missing value for --answers"))?;
+                answers = Some(path.into());
+            }
+            flag if flag.starts_with('-') => return Err(cli_error!("unknown option `{}`", flag)),
+            _ if days.is_some() => return Err(cli_error!("unexpected argument `{}`", arg)),
+            _ => days = Some(parse_days(&arg)?),
+        }
+    }
+
+    Ok(VerifyOptions {
+        days: days.unwrap_or(Days::All),
+        record,
+        answers,
+    })
+}
+
+fn parse_new(mut args: impl Iterator<Item = String>) -> Result<NewOptions, CliError> {
+    let mut day = None;
+    let mut title = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--title" => {
+                title = Some(args.next().ok_or_else(|| cli_error!("missing value for --title"))?);
+            }
+            flag if flag.starts_with('-') => return Err(cli_error!("unknown option `{}`", flag)),
+            _ if day.is_some() => return Err(cli_error!("unexpected argument `{}`", arg)),
+            _ => day = Some(parse_day(&arg)?),
+        }
+    }
+
+    let day = day.ok_or_else(|| cli_error!("missing day"))?;
+    let title = title.unwrap_or_else(|| format!("Day {}", day));
+
+    Ok(NewOptions { day, title })
+}
+
+fn parse_baseline_name(name: String) -> Result<String, CliError> {
+    match baseline::path(&name) {
+        Some(_) => Ok(name),
+        None => Err(cli_error!(
+            "invalid baseline name `{}`, use letters, digits, `-`, `_` and `.`",
+            name
+        )),
+    }
+}
+
+fn parse_bench(mut args: impl Iterator<Item = String>) -> Result<BenchOptions, CliError> {
+    let mut days = None;
+    let mut runs = 5;
+    let mut save = None;
+    let mut compare = None;
+    let mut threshold = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| cli_error!("missing value for {}", name))
+        };
+
+        match arg.as_str() {
+            "--all" => days = Some(Days::All),
+            "--runs" => runs = parse_count("runs", &value("--runs")?)?,
+            "--save" => save = Some(parse_baseline_name(value("--save")?)?),
+            "--compare" => compare = Some(parse_baseline_name(value("--compare")?)?),
+            "--threshold" => {
+                let value = value("--threshold")?;
+                let percent = value.strip_suffix('%').unwrap_or(&value);
+                threshold = Some(percent.parse().map_err(|_| {
+                    cli_error!("invalid threshold `{}`, expected a percentage like `10`", value)
+                })?);
+            }
+            flag if flag.starts_with('-') => return Err(cli_error!("unknown option `{}`", flag)),
+            _ if days.is_some() => return Err(cli_error!("unexpected argument `{}`", arg)),
+            _ => days = Some(parse_days(&arg)?),
+        }
+    }
+
+    if threshold.is_some() && compare.is_none() {
+        return Err(cli_error!("--threshold needs --compare"));
+    }
+
+    Ok(BenchOptions {
+        days: days.unwrap_or(Days::All),
+        runs,
+        save,
+        compare,
+        threshold: threshold.unwrap_or(10),
+    })
+}
+
+fn parse_crucible(mut args: impl Iterator<Item = String>) -> Result<CrucibleOptions, CliError> {
+    let mut rules = CrucibleRules::CRUCIBLE;
+    let mut input = InputSource::Puzzle;
+    let mut route = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| cli_error!("missing value for {}", name))
+        };
+
+        match arg.as_str() {
+            "--min-straight" => {
+                rules.min_straight = parse_count("blocks", &value("--min-straight")?)?;
+            }
+            "--max-straight" => {
+                rules.max_straight = parse_count("blocks", &value("--max-straight")?)?;
+            }
+            "--allow-reverse" => rules.allow_reverse = true,
+            "--input" => set_input(&mut input, InputSource::Path(value("--input")?.into()))?,
+            "--example" => set_input(&mut input, InputSource::Example)?,
+            "--route" => route = true,
+            flag if flag.starts_with('-') => return Err(cli_error!("unknown option `{}`", flag)),
+            _ => return Err(cli_error!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    if rules.min_straight > rules.max_straight {
+        return Err(cli_error!("--min-straight cannot be larger than --max-straight"));
+    }
+
+    Ok(CrucibleOptions {
+        rules,
+        input,
+        route,
+    })
+}
+
+/// Checks the day of the commands that only exist for day 20.
+fn only_day_20(day: Option<u8>, what: &str) -> Result<(), CliError> {
+    match day {
+        Some(20) => Ok(()),
+        Some(day) => Err(cli_error!("day {} cannot be {}, only day 20 can", day, what)),
+        None => Err(cli_error!("missing day")),
+    }
+}
+
+fn parse_trace(mut args: impl Iterator<Item = String>) -> Result<TraceOptions, CliError> {
+    let mut day = None;
+    let mut presses = 1;
+    let mut input = InputSource::Puzzle;
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| cli_error!("missing value for {}", name))
+        };
+
+        match arg.as_str() {
+            "--presses" => presses = parse_count("presses", &value("--presses")?)?,
+            "--input" => set_input(&mut input, InputSource::Path(value("--input")?.into()))?,
+            "--example" => set_input(&mut input, InputSource::Example)?,
+            "--output" => output = Some(value("--output")?.into()),
+            flag if flag.starts_with('-') => return Err(cli_error!("unknown option `{}`", flag)),
+            _ if day.is_some() => return Err(cli_error!("unexpected argument `{}`", arg)),
+            _ => day = Some(parse_day(&arg)?),
+        }
+    }
+
+    only_day_20(day, "traced")?;
+
+    Ok(TraceOptions {
+        presses,
+        input,
+        output: output.unwrap_or_else(|| "day20-trace.txt".into()),
+    })
+}
+
+fn parse_graph(mut args: impl Iterator<Item = String>) -> Result<GraphOptions, CliError> {
+    let mut day = None;
+    let mut input = InputSource::Puzzle;
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| cli_error!("missing value for {}", name))
+        };
+
+        match arg.as_str() {
+            "--input" => set_input(&mut input, InputSource::Path(value("--input")?.into()))?,
+            "--example" => set_input(&mut input, InputSource::Example)?,
+            "--output" => output = Some(value("--output")?.into()),
+            flag if flag.starts_with('-') => return Err(cli_error!("unknown option `{}`", flag)),
+            _ if day.is_some() => return Err(cli_error!("unexpected argument `{}`", arg)),
+            _ => day = Some(parse_day(&arg)?),
+        }
+    }
+
+    only_day_20(day, "drawn")?;
+
+    Ok(GraphOptions {
+        input,
+        output: output.unwrap_or_else(|| "day20.dot".into()),
+    })
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, CliError> {
+    let mut args = args.into_iter().peekable();
+
+    let command = match args.peek() {
+        Some(command) => command.clone(),
+        None => return Err(cli_error!("missing command")),
+    };
+
+    match command.as_str() {
+        "run" => parse_run(args.skip(1)).map(Command::Run),
+        "fetch" => parse_fetch(args.skip(1)).map(Command::Fetch),
+        "verify" => parse_verify(args.skip(1)).map(Command::Verify),
+        "new" => parse_new(args.skip(1)).map(Command::New),
+        "bench" => parse_bench(args.skip(1)).map(Command::Bench),
+        "crucible" => parse_crucible(args.skip(1)).map(Command::Crucible),
+        "trace" => parse_trace(args.skip(1)).map(Command::Trace),
+        "graph" => parse_graph(args.skip(1)).map(Command::Graph),
+        "list" => match args.nth(1) {
+            Some(arg) => Err(cli_error!("unexpected argument `{}`", arg)),
+            None => Ok(Command::List),
+        },
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        arg if arg.starts_with(|c: char| c.is_ascii_digit()) || arg == "--all" => {
+            parse_run(args).map(Command::Run)
+        }
+        _ => Err(cli_error!("unknown command `{}`", command)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, CliError> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
+
+    fn run(days: Days, part: Option<Part>, input: InputSource) -> Command {
+        Command::Run(RunOptions {
+            days,
+            part,
+            input,
+            timings: false,
+            budget: None,
+            format: Format::Text,
+            output: None,
+            jobs: 1,
+        })
+    }
+
+    #[test]
+    fn test_run() {
+        assert_eq!(
+            parse("run 3"),
+            Ok(run(Days::Range(3..=3), None, InputSource::Puzzle))
+        );
+        assert_eq!(
+            parse("run 3..=9 --part 2"),
+            Ok(run(Days::Range(3..=9), Some(Part::Two), InputSource::Puzzle))
+        );
+        assert_eq!(
+            parse("run 3..10 --example"),
+            Ok(run(Days::Range(3..=9), None, InputSource::Example))
+        );
+        assert_eq!(
+            parse("run --all --part 1"),
+            Ok(run(Days::All, Some(Part::One), InputSource::Puzzle))
+        );
+        assert_eq!(
+            parse("run 7 --input /tmp/day07.txt"),
+            Ok(run(
+                Days::Range(7..=7),
+                None,
+                InputSource::Path("/tmp/day07.txt".into())
+            ))
+        );
+        assert_eq!(
+            parse("12"),
+            Ok(run(Days::Range(12..=12), None, InputSource::Puzzle))
+        );
+        assert_eq!(
+            parse("run --all --budget 1.5s"),
+            Ok(Command::Run(RunOptions {
+                days: Days::All,
+                part: None,
+                input: InputSource::Puzzle,
+                timings: true,
+                budget: Some(Duration::from_millis(1500)),
+                format: Format::Text,
+                output: None,
+                jobs: 1,
+            }))
+        );
+        assert_eq!(
+            parse("run 1..=5 --format json --output runs.json"),
+            Ok(Command::Run(RunOptions {
+                days: Days::Range(1..=5),
+                part: None,
+                input: InputSource::Puzzle,
+                timings: false,
+                budget: None,
+                format: Format::Json,
+                output: Some("runs.json".into()),
+                jobs: 1,
+            }))
+        );
+        assert_eq!(
+            parse("run --all --jobs 4"),
+            Ok(Command::Run(RunOptions {
+                days: Days::All,
+                part: None,
+                input: InputSource::Puzzle,
+                timings: false,
+                budget: None,
+                format: Format::Text,
+                output: None,
+                jobs: 4,
+            }))
+        );
+        assert_eq!(parse("list"), Ok(Command::List));
+        assert_eq!(
+            parse("bench 1..=5 --save before --compare main --threshold 5%"),
+            Ok(Command::Bench(BenchOptions {
+                days: Days::Range(1..=5),
+                runs: 5,
+                save: Some("before".to_string()),
+                compare: Some("main".to_string()),
+                threshold: 5,
+            }))
+        );
+        assert_eq!(
+            parse("bench --runs 3"),
+            Ok(Command::Bench(BenchOptions {
+                days: Days::All,
+                runs: 3,
+                save: None,
+                compare: None,
+                threshold: 10,
+            }))
+        );
+        assert_eq!(
+            parse("new 23"),
+            Ok(Command::New(NewOptions {
+                day: 23,
+                title: "Day 23".to_string()
+            }))
+        );
+        assert_eq!(
+            parse("new 23 --title Walk"),
+            Ok(Command::New(NewOptions {
+                day: 23,
+                title: "Walk".to_string()
+            }))
+        );
+        assert_eq!(
+            parse("fetch 1..=3 --force"),
+            Ok(Command::Fetch(FetchOptions {
+                days: Days::Range(1..=3),
+                force: true
+            }))
+        );
+        assert_eq!(
+            parse("verify --record"),
+            Ok(Command::Verify(VerifyOptions {
+                days: Days::All,
+                record: true,
+                answers: None
+            }))
+        );
+        assert_eq!(
+            parse("crucible --min-straight 4 --max-straight 10 --example --route"),
+            Ok(Command::Crucible(CrucibleOptions {
+                rules: CrucibleRules::ULTRA_CRUCIBLE,
+                input: InputSource::Example,
+                route: true
+            }))
+        );
+        assert_eq!(
+            parse("crucible --allow-reverse"),
+            Ok(Command::Crucible(CrucibleOptions {
+                rules: CrucibleRules {
+                    allow_reverse: true,
+                    ..CrucibleRules::CRUCIBLE
+                },
+                input: InputSource::Puzzle,
+                route: false
+            }))
+        );
+        assert_eq!(
+            parse("trace 20 --presses 3 --example --output pulses.txt"),
+            Ok(Command::Trace(TraceOptions {
+                presses: 3,
+                input: InputSource::Example,
+                output: "pulses.txt".into()
+            }))
+        );
+        assert_eq!(
+            parse("trace 20"),
+            Ok(Command::Trace(TraceOptions {
+                presses: 1,
+                input: InputSource::Puzzle,
+                output: "day20-trace.txt".into()
+            }))
+        );
+        assert_eq!(
+            parse("graph 20 --output circuit.dot"),
+            Ok(Command::Graph(GraphOptions {
+                input: InputSource::Puzzle,
+                output: "circuit.dot".into()
+            }))
+        );
+        assert_eq!(parse("--help"), Ok(Command::Help));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(parse("").is_err());
+        assert!(parse("walk 3").is_err());
+        assert!(parse("run").is_err());
+        assert!(parse("run x").is_err());
+        assert!(parse("run 0").is_err());
+        assert!(parse("run 26").is_err());
+        assert!(parse("run 9..=3").is_err());
+        assert!(parse("run 3..3").is_err());
+        assert!(parse("run 3 --part 3").is_err());
+        assert!(parse("run 3 --part").is_err());
+        assert!(parse("run 3 --fast").is_err());
+        assert!(parse("run 3 4").is_err());
+        assert!(parse("run 3 --example --input a.txt").is_err());
+        assert!(parse("run --all --input a.txt").is_err());
+        assert!(parse("run --all --budget 1").is_err());
+        assert!(parse("run --all --budget 0s").is_err());
+        assert!(parse("run --all --budget fast").is_err());
+        assert!(parse("run 3 --format xml").is_err());
+        assert!(parse("run 3 --format json --timings").is_err());
+        assert!(parse("run 3 --output runs.json").is_err());
+        assert!(parse("run --all --jobs 0").is_err());
+        assert!(parse("run --all --jobs").is_err());
+        assert!(parse("list 3").is_err());
+        assert!(parse("fetch").is_err());
+        assert!(parse("fetch 3 --part 1").is_err());
+        assert!(parse("verify --answers").is_err());
+        assert!(parse("new").is_err());
+        assert!(parse("bench --save ../answers").is_err());
+        assert!(parse("bench --threshold 5").is_err());
+        assert!(parse("bench --compare main --threshold fast").is_err());
+        assert!(parse("bench --runs 0").is_err());
+        assert!(parse("new 1..=3").is_err());
+        assert!(parse("new 3 --title").is_err());
+        assert!(parse("crucible 17").is_err());
+        assert!(parse("crucible --min-straight 0").is_err());
+        assert!(parse("crucible --min-straight 4").is_err());
+        assert!(parse("crucible --max-straight").is_err());
+        assert!(parse("trace").is_err());
+        assert!(parse("trace 17").is_err());
+        assert!(parse("trace 20 --presses 0").is_err());
+        assert!(parse("graph 8").is_err());
+        assert!(parse("graph 20 --presses 3").is_err());
+    }
+
+    #[test]
+    fn test_days_from_str() {
+        assert_eq!("all".parse(), Ok(Days::All));
+        assert_eq!("20..=22".parse(), Ok(Days::Range(20..=22)));
+        assert!("20..".parse::<Days>().is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let days = Days::Range(1..=25).select().unwrap();
+        assert_eq!(days.len(), SOLUTIONS.len());
+
+        let days = Days::Range(3..=3).select().unwrap();
+        assert_eq!(days[0].day(), 3);
+
+        let result = Days::Range(25..=25).select();
+        assert!(matches!(result, Err(err) if err.0.starts_with("day 25 is not solved yet")));
+    }
+}
@@ -0,0 +1,427 @@
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Environment variable holding the `session` cookie of adventofcode.com.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Something that can produce the puzzle input for a day.
+pub trait InputProvider {
+    fn fetch(&self, day: u8) -> Result<String, FetchError>;
+
+    /// Checks that fetching can work at all, e.g. that a session cookie is
+    /// configured, before a request is throttled and sent.
+    fn ready(&self) -> Result<(), FetchError> {
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum FetchError {
+    /// No session cookie was configured.
+    MissingSession,
+    /// The server answered with an error status.
+    Status { day: u8, status: u16, body: String },
+    /// The request never got an answer, e.g. because the server is down.
+    Transport(String),
+    /// Reading or writing a local file failed.
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::MissingSession => write!(
+                f,
+                "{} is not set, copy the value of the `session` cookie from adventofcode.com",
+                SESSION_VAR
+            ),
+            FetchError::Status { day, status: 404, .. } => {
+                write!(f, "the input for day {} is not available yet", day)
+            }
+            FetchError::Status { day, status: 400 | 500, .. } => write!(
+                f,
+                "could not download the input for day {}, is the session cookie still valid?",
+                day
+            ),
+            FetchError::Status { day, status, body } => write!(
+                f,
+                "could not download the input for day {}: status {}: {}",
+                day,
+                status,
+                body.trim()
+            ),
+            FetchError::Transport(err) => write!(f, "request failed: {}", err),
+            FetchError::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+/// Downloads inputs over HTTP, authenticated with a session cookie.
+pub struct HttpProvider {
+    base_url: String,
+    /// `None` if no cookie was configured, which only fails once an input
+    /// actually has to be downloaded.
+    session: Option<String>,
+    agent: ureq::Agent,
+}
+
+impl HttpProvider {
+    pub const BASE_URL: &'static str = "https://adventofcode.com/2023";
+
+    pub fn new(session: impl Into<String>) -> Self {
+        Self::with_base_url(Self::BASE_URL, session)
+    }
+
+    /// Creates a provider talking to another server, e.g. a local stub.
+    pub fn with_base_url(base_url: impl Into<String>, session: impl Into<String>) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!(
+                env!("CARGO_PKG_NAME"),
+                "/",
+                env!("CARGO_PKG_VERSION"),
+                " (aoc fetch)"
+            ))
+            .build();
+
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            session: Some(session.into()),
+            agent,
+        }
+    }
+
+    /// Creates a provider with the session cookie from `AOC_SESSION`.
+    ///
+    /// Without the variable, fetching fails with
+    /// [`FetchError::MissingSession`], so inputs that are already cached can
+    /// still be used offline.
+    pub fn from_env() -> Self {
+        let mut provider = Self::new("");
+        provider.session = env::var(SESSION_VAR)
+            .ok()
+            .map(|session| session.trim().to_string())
+            .filter(|session| !session.is_empty());
+        provider
+    }
+}
+
+impl InputProvider for HttpProvider {
+    fn ready(&self) -> Result<(), FetchError> {
+        self.session.as_ref().map(|_| ()).ok_or(FetchError::MissingSession)
+    }
+
+    fn fetch(&self, day: u8) -> Result<String, FetchError> {
+        let session = self.session.as_ref().ok_or(FetchError::MissingSession)?;
+        let url = format!("{}/day/{}/input", self.base_url, day);
+        let request = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", session));
+
+        let response = match request.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(status, response)) => {
+                return Err(FetchError::Status {
+                    day,
+                    status,
+                    body: response.into_string().unwrap_or_default(),
+                })
+            }
+            Err(err) => return Err(FetchError::Transport(err.to_string())),
+        };
+
+        response
+            .into_string()
+            .map_err(|err| FetchError::Transport(err.to_string()))
+    }
+}
+
+/// Reads inputs from `dayNN.txt` files in a directory, for tests and mirrors.
+pub struct DirProvider {
+    dir: PathBuf,
+}
+
+impl DirProvider {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl InputProvider for DirProvider {
+    fn fetch(&self, day: u8) -> Result<String, FetchError> {
+        let path = self.dir.join(format!("day{:02}.txt", day));
+        fs::read_to_string(&path).map_err(|err| FetchError::Io(path, err))
+    }
+}
+
+/// The result of [`Cache::get`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fetched {
+    pub input: String,
+    pub path: PathBuf,
+    /// Whether the input was already on disk and the provider was not asked.
+    pub cached: bool,
+}
+
+/// Stores inputs from another provider as `dayNN.txt` files in a directory.
+///
+/// Inputs never change, so a non-empty file is always used as is. Requests to
+/// the provider are spaced at least `min_interval` apart, also across runs:
+/// the time of the last request is kept in a `.last-fetch` file next to the
+/// inputs.
+pub struct Cache<P> {
+    provider: P,
+    dir: PathBuf,
+    min_interval: Duration,
+}
+
+impl<P: InputProvider> Cache<P> {
+    pub const MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+    pub fn new(provider: P, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            provider,
+            dir: dir.into(),
+            min_interval: Self::MIN_INTERVAL,
+        }
+    }
+
+    pub fn min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day{:02}.txt", day))
+    }
+
+    /// Returns the cached input for `day`, fetching it if it is missing or
+    /// empty, or if `force` is set.
+    pub fn get(&self, day: u8, force: bool) -> Result<Fetched, FetchError> {
+        let path = self.path(day);
+
+        if !force {
+            match fs::read_to_string(&path) {
+                Ok(input) if !input.is_empty() => {
+                    return Ok(Fetched {
+                        input,
+                        path,
+                        cached: true,
+                    })
+                }
+                Ok(_) => (),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+                Err(err) => return Err(FetchError::Io(path, err)),
+            }
+        }
+
+        self.provider.ready()?;
+        self.throttle();
+        let input = self.provider.fetch(day);
+        write_stamp(&self.stamp())?;
+        let input = input?;
+
+        fs::create_dir_all(&self.dir).map_err(|err| FetchError::Io(self.dir.clone(), err))?;
+        fs::write(&path, &input).map_err(|err| FetchError::Io(path.clone(), err))?;
+
+        Ok(Fetched {
+            input,
+            path,
+            cached: false,
+        })
+    }
+
+    /// The file holding the time of the last request.
+    fn stamp(&self) -> PathBuf {
+        self.dir.join(".last-fetch")
+    }
+
+    /// Waits until `min_interval` has passed since the last request. The
+    /// stamp is only written once the next request went out.
+    fn throttle(&self) {
+        let stamp = self.stamp();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        let last = fs::read_to_string(&stamp)
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .map(Duration::from_millis);
+
+        if let Some(last) = last {
+            // A timestamp in the future means the clock changed, wait the full interval.
+            let elapsed = now.checked_sub(last).unwrap_or_default();
+            thread::sleep(self.min_interval.saturating_sub(elapsed));
+        }
+    }
+}
+
+fn write_stamp(path: &Path) -> Result<(), FetchError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|err| FetchError::Io(dir.to_path_buf(), err))?;
+    }
+
+    fs::write(path, now.as_millis().to_string()).map_err(|err| FetchError::Io(path.to_path_buf(), err))
+}
+
+impl<P: InputProvider> InputProvider for Cache<P> {
+    fn fetch(&self, day: u8) -> Result<String, FetchError> {
+        self.get(day, false).map(|fetched| fetched.input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        cell::Cell,
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::mpsc,
+        time::Instant,
+    };
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-fetch-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// Serves one canned response per connection and reports the request lines.
+    fn stub_server(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/2023", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let request = BufReader::new(&stream)
+                    .lines()
+                    .map(Result::unwrap)
+                    .take_while(|line| !line.is_empty())
+                    .collect();
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+
+                sender.send(request).unwrap();
+            }
+        });
+
+        (url, receiver)
+    }
+
+    struct Counting<'a>(&'a Cell<usize>);
+
+    impl InputProvider for Counting<'_> {
+        fn fetch(&self, day: u8) -> Result<String, FetchError> {
+            self.0.set(self.0.get() + 1);
+            Ok(format!("input {}\n", day))
+        }
+    }
+
+    #[test]
+    fn test_http_provider() {
+        let (url, requests) = stub_server(vec![(200, "1abc2\n"), (404, "Not Found")]);
+        let provider = HttpProvider::with_base_url(url, "cookie");
+
+        assert_eq!(provider.fetch(1).unwrap(), "1abc2\n");
+        let request = requests.recv().unwrap();
+        assert_eq!(request[0], "GET /2023/day/1/input HTTP/1.1");
+        assert!(request.iter().any(|header| header == "Cookie: session=cookie"));
+
+        let err = provider.fetch(25).unwrap_err();
+        assert!(matches!(err, FetchError::Status { day: 25, status: 404, .. }));
+        assert_eq!(err.to_string(), "the input for day 25 is not available yet");
+    }
+
+    #[test]
+    fn test_cache() {
+        let dir = temp_dir("cache");
+        let calls = Cell::new(0);
+        let cache = Cache::new(Counting(&calls), &dir).min_interval(Duration::ZERO);
+
+        let fetched = cache.get(3, false).unwrap();
+        assert_eq!(fetched.input, "input 3\n");
+        assert!(!fetched.cached);
+        assert_eq!(fs::read_to_string(dir.join("day03.txt")).unwrap(), "input 3\n");
+
+        assert!(cache.get(3, false).unwrap().cached);
+        assert_eq!(calls.get(), 1);
+
+        assert!(!cache.get(3, true).unwrap().cached);
+        assert_eq!(calls.get(), 2);
+
+        fs::write(dir.join("day04.txt"), "").unwrap();
+        assert_eq!(cache.fetch(4).unwrap(), "input 4\n");
+        assert_eq!(calls.get(), 3);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_session() {
+        let dir = temp_dir("missing-session");
+        let provider = HttpProvider {
+            session: None,
+            ..HttpProvider::new("")
+        };
+        let cache = Cache::new(provider, &dir).min_interval(Duration::from_secs(60));
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day01.txt"), "1abc2\n").unwrap();
+        assert!(cache.get(1, false).unwrap().cached);
+
+        // Fails right away, without waiting for or recording a request.
+        let start = Instant::now();
+        assert!(matches!(cache.get(2, false), Err(FetchError::MissingSession)));
+        assert!(matches!(cache.get(1, true), Err(FetchError::MissingSession)));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(!dir.join(".last-fetch").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let dir = temp_dir("rate-limit");
+        let calls = Cell::new(0);
+        let cache = Cache::new(Counting(&calls), &dir).min_interval(Duration::from_millis(200));
+
+        let start = Instant::now();
+        cache.get(1, false).unwrap();
+        cache.get(2, false).unwrap();
+        // The timestamp only has millisecond precision.
+        assert!(start.elapsed() >= Duration::from_millis(190));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_dir_provider() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join("inputs");
+        let provider = DirProvider::new(dir);
+
+        assert!(provider.fetch(7).unwrap().starts_with("467K3 95"));
+        assert!(matches!(provider.fetch(26), Err(FetchError::Io(..))));
+    }
+}
@@ -0,0 +1,287 @@
+use std::ops::{Index, IndexMut};
+
+use ::grid::Grid;
+
+/// A turn by 90 degrees, like the `L` and `R` of a map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl TryFrom<char> for Turn {
+    type Error = &'static str;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'L' => Ok(Self::Left),
+            'R' => Ok(Self::Right),
+            _ => Err("invalid turn"),
+        }
+    }
+}
+
+/// One of the four directions along the rows and columns of a grid.
+///
+/// Up is towards row 0 and left towards column 0. The variants are in
+/// clockwise order, `direction as usize` can be used as an index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction4 {
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    /// The `(row, column)` offset of one step in this direction.
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Self::Up => (-1, 0),
+            Self::Right => (0, 1),
+            Self::Down => (1, 0),
+            Self::Left => (0, -1),
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn turn(self, turn: Turn) -> Self {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right(),
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Self::Up | Self::Down)
+    }
+
+    /// The arrow pointing this way, as drawn in the puzzle descriptions.
+    pub fn arrow(self) -> char {
+        match self {
+            Self::Up => '^',
+            Self::Right => '>',
+            Self::Down => 'v',
+            Self::Left => '<',
+        }
+    }
+}
+
+/// One of the eight directions to the neighbours of a cell, including the
+/// diagonals.
+///
+/// The variants are in clockwise order, starting with up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    pub const ALL: [Self; 8] = [
+        Self::Up,
+        Self::UpRight,
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+    ];
+
+    /// The `(row, column)` offset of one step in this direction.
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Self::Up => (-1, 0),
+            Self::UpRight => (-1, 1),
+            Self::Right => (0, 1),
+            Self::DownRight => (1, 1),
+            Self::Down => (1, 0),
+            Self::DownLeft => (1, -1),
+            Self::Left => (0, -1),
+            Self::UpLeft => (-1, -1),
+        }
+    }
+
+    /// Turns clockwise by 45 degrees.
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Turns counterclockwise by 45 degrees.
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+}
+
+impl From<Direction4> for Direction8 {
+    fn from(direction: Direction4) -> Self {
+        match direction {
+            Direction4::Up => Self::Up,
+            Direction4::Right => Self::Right,
+            Direction4::Down => Self::Down,
+            Direction4::Left => Self::Left,
+        }
+    }
+}
+
+/// The position of a cell in a grid, as row `y` and column `x`.
+///
+/// Grids can be indexed with points directly, `grid[point]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Point {
+    pub y: usize,
+    pub x: usize,
+}
+
+impl Point {
+    pub const fn new(y: usize, x: usize) -> Self {
+        Self { y, x }
+    }
+
+    /// Moves by `(dy, dx)`, or `None` if that leaves row or column 0 behind.
+    pub fn offset(self, (dy, dx): (isize, isize)) -> Option<Self> {
+        Some(Self {
+            y: self.y.checked_add_signed(dy)?,
+            x: self.x.checked_add_signed(dx)?,
+        })
+    }
+
+    /// Moves one step in `direction`, or `None` if that leaves row or column
+    /// 0 behind.
+    pub fn step(self, direction: Direction4) -> Option<Self> {
+        self.offset(direction.delta())
+    }
+
+    /// Moves one step in `direction`, or `None` if that leaves `grid`.
+    pub fn step_in<T>(self, direction: Direction4, grid: &Grid<T>) -> Option<Self> {
+        self.step(direction).filter(|next| next.is_in(grid))
+    }
+
+    pub fn is_in<T>(self, grid: &Grid<T>) -> bool {
+        self.y < grid.rows() && self.x < grid.cols()
+    }
+
+    /// The up to four orthogonal neighbours inside `grid`, with the direction
+    /// they are in.
+    pub fn neighbours<T>(self, grid: &Grid<T>) -> impl Iterator<Item = (Direction4, Self)> + '_ {
+        Direction4::ALL
+            .into_iter()
+            .filter_map(move |direction| Some((direction, self.step_in(direction, grid)?)))
+    }
+
+    /// The up to eight neighbours inside `grid`, including the diagonal ones.
+    pub fn neighbours8<T>(self, grid: &Grid<T>) -> impl Iterator<Item = (Direction8, Self)> + '_ {
+        Direction8::ALL.into_iter().filter_map(move |direction| {
+            let next = self.offset(direction.delta())?;
+            next.is_in(grid).then_some((direction, next))
+        })
+    }
+
+    pub fn manhattan(self, other: Self) -> usize {
+        self.y.abs_diff(other.y) + self.x.abs_diff(other.x)
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((y, x): (usize, usize)) -> Self {
+        Self { y, x }
+    }
+}
+
+impl From<Point> for (usize, usize) {
+    fn from(point: Point) -> Self {
+        (point.y, point.x)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        &self[(point.y, point.x)]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        &mut self[(point.y, point.x)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction4() {
+        for direction in Direction4::ALL {
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.reverse());
+            assert_eq!(direction.reverse().reverse(), direction);
+
+            let (dy, dx) = direction.delta();
+            assert_eq!(direction.reverse().delta(), (-dy, -dx));
+            assert_eq!(Direction8::from(direction).delta(), (dy, dx));
+        }
+
+        assert_eq!(Direction4::Up.turn_right(), Direction4::Right);
+        assert_eq!(Direction4::Up.turn(Turn::Left), Direction4::Left);
+        assert!(Direction4::Down.is_vertical());
+        assert_eq!(Direction4::Left.arrow(), '<');
+    }
+
+    #[test]
+    fn test_direction8() {
+        for direction in Direction8::ALL {
+            let (dy, dx) = direction.delta();
+            assert_eq!(direction.reverse().delta(), (-dy, -dx));
+            assert_eq!(direction.turn_left().turn_right(), direction);
+        }
+
+        assert_eq!(Direction8::Up.turn_right(), Direction8::UpRight);
+        assert_eq!(Direction8::Up.turn_left(), Direction8::UpLeft);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::from_vec((0..6).collect(), 3);
+
+        let corner: Vec<_> = Point::new(0, 0).neighbours(&grid).collect();
+        assert_eq!(
+            corner,
+            [(Direction4::Right, Point::new(0, 1)), (Direction4::Down, Point::new(1, 0))]
+        );
+
+        assert_eq!(Point::new(1, 1).neighbours(&grid).count(), 3);
+        assert_eq!(Point::new(0, 1).neighbours8(&grid).count(), 5);
+        assert_eq!(Point::new(1, 2).step_in(Direction4::Right, &grid), None);
+        assert_eq!(Point::new(1, 2).step(Direction4::Right), Some(Point::new(1, 3)));
+
+        assert_eq!(grid[Point::new(1, 2)], 5);
+        assert_eq!(Point::new(0, 2).manhattan(Point::new(1, 0)), 3);
+    }
+}
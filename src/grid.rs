@@ -0,0 +1,146 @@
+use std::fmt;
+
+use ::grid::Grid;
+
+use crate::parse::char_at;
+use crate::ParseError;
+
+/// A grid read from the puzzle input, with the positions of its marker cells.
+#[derive(Debug, Clone)]
+pub struct ParsedGrid<T> {
+    pub grid: Grid<T>,
+    /// Every cell holding one of the requested marker characters, as
+    /// `(marker, (row, column))` in reading order.
+    pub markers: Vec<(char, (usize, usize))>,
+}
+
+impl<T> ParsedGrid<T> {
+    /// The position of the first `marker` cell.
+    pub fn marker(&self, marker: char) -> Option<(usize, usize)> {
+        self.markers
+            .iter()
+            .find(|(c, _)| *c == marker)
+            .map(|&(_, position)| position)
+    }
+}
+
+/// Parses a rectangular grid with one cell per character.
+///
+/// Every character is converted with `T::try_from`, its error becomes the
+/// message of the [`ParseError`]. The positions of all characters in
+/// `markers`, like the `S` of a starting point, are collected on the way.
+pub fn parse_grid<T>(input: &str, markers: &[char]) -> Result<ParsedGrid<T>, ParseError>
+where
+    T: TryFrom<char>,
+    T::Error: fmt::Display,
+{
+    parse_grid_with(input, input, markers, T::try_from)
+}
+
+/// Parses the grid in `text`, a slice of `input`, converting the cells with
+/// `cell`.
+///
+/// Useful for inputs holding several grids and for cells that do not map to a
+/// type of their own, errors still point into the whole `input`.
+pub fn parse_grid_with<T, E: fmt::Display>(
+    input: &str,
+    text: &str,
+    markers: &[char],
+    mut cell: impl FnMut(char) -> Result<T, E>,
+) -> Result<ParsedGrid<T>, ParseError> {
+    let mut data = Vec::new();
+    let mut found = Vec::new();
+    let mut cols = None;
+
+    for (y, line) in text.lines().enumerate() {
+        let width = line.chars().count();
+        let expected = *cols.get_or_insert(width);
+
+        if width != expected {
+            let message = format!("row has {} cells, expected {} like the first row", width, expected);
+            return Err(ParseError::at(input, line, message));
+        }
+
+        for (x, (idx, c)) in line.char_indices().enumerate() {
+            let value = cell(c).map_err(|err| ParseError::at(input, char_at(line, idx), err.to_string()))?;
+
+            if markers.contains(&c) {
+                found.push((c, (y, x)));
+            }
+
+            data.push(value);
+        }
+    }
+
+    let cols = match cols {
+        Some(cols) if cols > 0 => cols,
+        _ => return Err(ParseError::at(input, text, "empty grid")),
+    };
+
+    Ok(ParsedGrid {
+        grid: Grid::from_vec(data, cols),
+        markers: found,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Tile {
+        Wall,
+        Open,
+    }
+
+    impl TryFrom<char> for Tile {
+        type Error = &'static str;
+
+        fn try_from(c: char) -> Result<Self, Self::Error> {
+            match c {
+                '#' => Ok(Self::Wall),
+                '.' | 'S' | 'E' => Ok(Self::Open),
+                _ => Err("invalid tile"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_grid() {
+        let parsed = parse_grid::<Tile>("#S.\n..E\n#.S", &['S', 'E']).unwrap();
+
+        assert_eq!((parsed.grid.rows(), parsed.grid.cols()), (3, 3));
+        assert_eq!(parsed.grid[(0, 0)], Tile::Wall);
+        assert_eq!(parsed.grid[(1, 2)], Tile::Open);
+        assert_eq!(parsed.markers, [('S', (0, 1)), ('E', (1, 2)), ('S', (2, 2))]);
+        assert_eq!(parsed.marker('E'), Some((1, 2)));
+        assert_eq!(parsed.marker('X'), None);
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = parse_grid::<Tile>("#..\n#.\n...", &[]).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.message, "row has 2 cells, expected 3 like the first row");
+
+        let err = parse_grid::<Tile>("#..\n#x.", &[]).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "x"));
+        assert_eq!(err.message, "invalid tile");
+
+        assert!(parse_grid::<Tile>("", &[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_grid_with() {
+        let input = "12\n34\n\n5x\n67";
+        let (_, second) = input.split_once("\n\n").unwrap();
+
+        let digit = |c: char| c.to_digit(10).ok_or("invalid digit");
+        let err = parse_grid_with(input, second, &[], digit).unwrap_err();
+        assert_eq!((err.line, err.column), (4, 2));
+
+        let (first, _) = input.split_once("\n\n").unwrap();
+        let parsed = parse_grid_with(input, first, &[], digit).unwrap();
+        assert_eq!(parsed.grid[(1, 0)], 3);
+    }
+}
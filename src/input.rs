@@ -0,0 +1,203 @@
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::answers::Answers;
+
+/// Environment variable pointing at a directory that contains the `inputs`
+/// and `examples` folders, checked before the ones shipped in `src/`.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// An input file that could not be loaded, with every path that was tried.
+#[derive(Debug)]
+pub struct InputError {
+    pub file: String,
+    pub tried: Vec<(PathBuf, io::Error)>,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "could not load input `{}`", self.file)?;
+
+        for (path, err) in &self.tried {
+            write!(f, "\n    {}: {}", path.display(), err)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// The directories searched for the `inputs` and `examples` folders, in order.
+///
+/// `AOC_INPUT_DIR` comes first so local inputs can live outside the
+/// repository, followed by the `src` directory of this crate. The crate
+/// directory is baked in at compile time, so neither depends on the directory
+/// the binary or the tests are started from.
+pub fn search_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Some(dir) = env::var_os(INPUT_DIR_VAR).filter(|dir| !dir.is_empty()) {
+        dirs.push(PathBuf::from(dir));
+    }
+
+    dirs.push(Path::new(env!("CARGO_MANIFEST_DIR")).join("src"));
+    dirs
+}
+
+fn load_from(dirs: &[PathBuf], folder: &str, file: String) -> Result<String, InputError> {
+    let mut tried = Vec::new();
+
+    for dir in dirs {
+        let path = dir.join(folder).join(&file);
+
+        match fs::read_to_string(&path) {
+            Ok(input) => return Ok(input),
+            Err(err) => tried.push((path, err)),
+        }
+    }
+
+    Err(InputError {
+        file: format!("{}/{}", folder, file),
+        tried,
+    })
+}
+
+/// Loads `dayNN.txt` from `folder`, usually `inputs` or `examples`.
+pub fn load(folder: &str, day: u8) -> Result<String, InputError> {
+    load_from(&search_dirs(), folder, format!("day{:02}.txt", day))
+}
+
+/// Loads the example `examples/dayNN/<name>.txt`.
+pub fn load_example(day: u8, name: &str) -> Result<String, InputError> {
+    load_from(&search_dirs(), "examples", format!("day{:02}/{}.txt", day, name))
+}
+
+/// A named example of a day together with the answers it should give.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub name: String,
+    pub input: String,
+    /// The expected answers from the `<name>.toml` next to the example, in
+    /// the format of `answers.toml`. Parts the example is not meant for are
+    /// left out, an example without that file has no expected answers.
+    pub expected: Answers,
+}
+
+fn examples_from(dirs: &[PathBuf], day: u8) -> Result<Vec<Example>, InputError> {
+    let folder = format!("examples/day{:02}", day);
+    let mut tried = Vec::new();
+
+    let Some((dir, entries)) = dirs.iter().find_map(|dir| {
+        let dir = dir.join(&folder);
+
+        match fs::read_dir(&dir) {
+            Ok(entries) => Some((dir, entries)),
+            Err(err) => {
+                tried.push((dir, err));
+                None
+            }
+        }
+    }) else {
+        return Err(InputError { file: folder, tried });
+    };
+
+    let error = |path: PathBuf, err: io::Error| InputError {
+        file: path.display().to_string(),
+        tried: vec![(path, err)],
+    };
+
+    let mut names = Vec::new();
+
+    for entry in entries {
+        let path = entry.map_err(|err| error(dir.clone(), err))?.path();
+
+        if path.extension().is_some_and(|ext| ext == "txt") {
+            if let Some(name) = path.file_stem().and_then(|name| name.to_str()) {
+                names.push(name.to_string());
+            }
+        }
+    }
+
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let path = dir.join(format!("{}.txt", name));
+            let input = fs::read_to_string(&path).map_err(|err| error(path, err))?;
+
+            let path = dir.join(format!("{}.toml", name));
+            let expected = Answers::load(&path).map_err(|err| error(path, err))?;
+
+            Ok(Example {
+                name,
+                input,
+                expected,
+            })
+        })
+        .collect()
+}
+
+/// All examples of `day` from `examples/dayNN/`, sorted by name.
+///
+/// Only the first directory of [`search_dirs`] that has examples for the day
+/// is used.
+pub fn examples(day: u8) -> Result<Vec<Example>, InputError> {
+    examples_from(&search_dirs(), day)
+}
+
+/// Loads an input from an explicit path.
+pub fn load_path(path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|err| InputError {
+        file: path.display().to_string(),
+        tried: vec![(path.to_path_buf(), err)],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_from() {
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let missing = crate_dir.join("missing");
+
+        let dirs = [missing.clone(), crate_dir];
+
+        let input = load_from(&dirs, "examples", "day07/example.txt".into()).unwrap();
+        assert!(input.starts_with("32T3K 765"));
+
+        let err = load_from(&dirs[..1], "inputs", "day26.txt".into()).unwrap_err();
+        assert_eq!(err.file, "inputs/day26.txt");
+        assert_eq!(err.tried.len(), 1);
+        assert_eq!(err.tried[0].0, missing.join("inputs").join("day26.txt"));
+    }
+
+    #[test]
+    fn test_examples_from() {
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let dirs = [crate_dir.join("missing"), crate_dir];
+
+        let examples = examples_from(&dirs, 1).unwrap();
+        let names: Vec<_> = examples.iter().map(|example| example.name.as_str()).collect();
+        assert_eq!(names, ["example", "example2"]);
+
+        assert_eq!(examples[0].expected.get(1, 1), Some(&142.into()));
+        assert_eq!(examples[0].expected.get(1, 2), None);
+        assert_eq!(examples[1].expected.get(1, 2), Some(&281.into()));
+
+        let err = examples_from(&dirs, 26).unwrap_err();
+        assert_eq!(err.file, "examples/day26");
+        assert_eq!(err.tried.len(), 2);
+    }
+
+    #[test]
+    fn test_load_path() {
+        let err = load_path(Path::new("missing/day01.txt")).unwrap_err();
+        assert!(err.to_string().starts_with("could not load input `missing/day01.txt`\n"));
+    }
+}
@@ -1,29 +1,35 @@
 #![feature(array_windows)]
-use std::env;
-use std::fs;
 
+mod answer;
+pub mod answers;
+pub mod baseline;
+pub mod cli;
+pub mod fetch;
+pub mod geom;
+pub mod grid;
+pub mod input;
+pub mod output;
+pub mod parse;
+pub mod scaffold;
+pub mod search;
+mod solution;
 pub mod solutions;
+pub mod timings;
 
-pub fn read_file(folder: &str, day: u8) -> String {
-    let cwd = env::current_dir().unwrap();
-
-    let filepath = cwd
-        .join("src")
-        .join(folder)
-        .join(format!("day{:02}.txt", day));
+pub use answer::Answer;
+pub use parse::ParseError;
+pub use solution::{Parsed, SolveError, Solution};
 
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+/// Loads `dayNN.txt` from `folder`, panicking if it cannot be found.
+///
+/// A shorthand for tests and benchmarks, see [`input::load`] for the
+/// fallible version.
+pub fn read_file(folder: &str, day: u8) -> String {
+    input::load(folder, day).unwrap_or_else(|err| panic!("{}", err))
 }
 
-pub fn read_file_with_name(folder: &str, name: &str) -> String {
-    let cwd = env::current_dir().unwrap();
-
-    let filepath = cwd
-        .join("src")
-        .join(folder)
-        .join(format!("day{}.txt", name));
-
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
-}
\ No newline at end of file
+/// Loads the example `examples/dayNN/<name>.txt`, panicking if it cannot be
+/// found.
+pub fn read_example(day: u8, name: &str) -> String {
+    input::load_example(day, name).unwrap_or_else(|err| panic!("{}", err))
+}
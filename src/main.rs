@@ -1,70 +1,758 @@
-use aoc::read_file;
 use std::env;
-use std::fmt::Display;
-use std::time::Instant;
-
-use aoc::solutions::*;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
 
+use aoc::answers::{self, Answers, Verdict};
+use aoc::baseline::{self, Baseline, STEPS};
+use aoc::cli::{
+    self, BenchOptions, Command, CrucibleOptions, FetchOptions, Format, GraphOptions, InputSource,
+    NewOptions, Part, RunOptions, TraceOptions, VerifyOptions,
+};
+use aoc::fetch::{Cache, HttpProvider};
+use aoc::input::{self, InputError};
+use aoc::output::{self, Record};
+use aoc::scaffold;
+use aoc::solutions::{day17, day20, SOLUTIONS};
+use aoc::timings::{DayTimings, Report};
+use aoc::{Answer, ParseError, Parsed, SolveError, Solution};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use rayon::{ThreadPoolBuildError, ThreadPoolBuilder};
 
 static ANSI_ITALIC: &str = "\x1b[3m";
 static ANSI_BOLD: &str = "\x1b[1m";
 static ANSI_RESET: &str = "\x1b[0m";
 
-fn print_result<T: Display>(func: impl FnOnce(&str) -> T, input: &str) {
+/// Why a day could not be run at all.
+enum DayError {
+    Load(InputError),
+    Parse(ParseError),
+}
+
+/// The answer to one part and how long it took.
+struct PartRun {
+    part: Part,
+    answer: Result<Answer, SolveError>,
+    elapsed: Duration,
+}
+
+/// Everything that happened while running one day.
+struct DayRun {
+    solution: &'static dyn Solution,
+    input: String,
+    load: Duration,
+    parse: Duration,
+    parts: Result<Vec<PartRun>, DayError>,
+}
+
+fn selected_parts(part: Option<Part>) -> Vec<Part> {
+    [Part::One, Part::Two]
+        .into_iter()
+        .filter(|current| part.is_none_or(|part| part == *current))
+        .collect()
+}
+
+fn solve_part(solution: &dyn Solution, input: &Parsed, part: Part) -> Result<Answer, SolveError> {
+    match part {
+        Part::One => solution.part_one(input),
+        Part::Two => solution.part_two(input),
+    }
+}
+
+fn load_input(source: &InputSource, day: u8) -> Result<String, InputError> {
+    match source {
+        InputSource::Puzzle => input::load("inputs", day),
+        InputSource::Example => input::load_example(day, "example"),
+        InputSource::Path(path) => input::load_path(path),
+    }
+}
+
+/// Loads, parses and solves one day, reporting each step to `progress`.
+fn run_day(solution: &'static dyn Solution, options: &RunOptions, progress: &ProgressBar) -> DayRun {
+    let mut run = DayRun {
+        solution,
+        input: String::new(),
+        load: Duration::ZERO,
+        parse: Duration::ZERO,
+        parts: Ok(Vec::new()),
+    };
+
+    progress.set_message("loading");
     let timer = Instant::now();
-    let result = func(input);
-    let time = timer.elapsed();
-    println!(
-        "{} {}(elapsed: {:.2?}){}",
-        result, ANSI_ITALIC, time, ANSI_RESET
-    );
-}
-
-macro_rules! solve_day {
-    ($day:path, $input:expr) => {{
-        use $day::*;
-        println!("----");
-        println!("");
-        println!("🎄 {}Part 1{} 🎄", ANSI_BOLD, ANSI_RESET);
-        println!("");
-        print_result(part_one, $input);
-        println!("");
-        println!("🎄 {}Part 2{} 🎄", ANSI_BOLD, ANSI_RESET);
-        println!("");
-        print_result(part_two, $input);
-        println!("");
-        println!("----");
-    }};
-}
-
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    let day: u8 = args[1].clone().parse().unwrap();
-    let input = read_file("inputs", day);
-
-    match day {
-        1 => solve_day!(day01, &input),
-        2 => solve_day!(day02, &input),
-        3 => solve_day!(day03, &input),
-        4 => solve_day!(day04, &input),
-        5 => solve_day!(day05, &input),
-        6 => solve_day!(day06, &input),
-        7 => solve_day!(day07, &input),
-        8 => solve_day!(day08, &input),
-        9 => solve_day!(day09, &input),
-        10 => solve_day!(day10, &input),
-        11 => solve_day!(day11, &input),
-        12 => solve_day!(day12, &input),
-        13 => solve_day!(day13, &input),
-        14 => solve_day!(day14, &input),
-        15 => solve_day!(day15, &input),
-        16 => solve_day!(day16, &input),
-        17 => solve_day!(day17, &input),
-        18 => solve_day!(day18, &input),
-        19 => solve_day!(day19, &input),
-        20 => solve_day!(day20, &input),
-        21 => solve_day!(day21, &input),
-        22 => solve_day!(day22, &input),
-        _ => println!("day not solved: {}", day),
-    }
-}
\ No newline at end of file
+    run.input = match load_input(&options.input, solution.day()) {
+        Ok(input) => input,
+        Err(err) => {
+            run.parts = Err(DayError::Load(err));
+            return run;
+        }
+    };
+    run.load = timer.elapsed();
+
+    progress.set_message("parsing");
+    let timer = Instant::now();
+    let input = match solution.parse(&run.input) {
+        Ok(input) => input,
+        Err(err) => {
+            run.parts = Err(DayError::Parse(err));
+            return run;
+        }
+    };
+    run.parse = timer.elapsed();
+    progress.inc(1);
+
+    let parts = selected_parts(options.part)
+        .into_iter()
+        .map(|part| {
+            progress.set_message(format!("part {}", part.number()));
+            let timer = Instant::now();
+            let answer = solve_part(solution, &input, part);
+            let elapsed = timer.elapsed();
+            progress.inc(1);
+
+            PartRun {
+                part,
+                answer,
+                elapsed,
+            }
+        })
+        .collect();
+
+    run.parts = Ok(parts);
+    run
+}
+
+/// Runs the days on `jobs` threads, with a progress bar for every day.
+///
+/// The runs are returned in the same order as `solutions`.
+fn run_parallel(
+    solutions: &[&'static dyn Solution],
+    options: &RunOptions,
+) -> Result<Vec<DayRun>, ThreadPoolBuildError> {
+    let pool = ThreadPoolBuilder::new().num_threads(options.jobs).build()?;
+
+    let bars = MultiProgress::new();
+    let style = ProgressStyle::with_template("{prefix:>6} [{bar:20}] {msg}")
+        .unwrap()
+        .progress_chars("=> ");
+    let steps = 1 + selected_parts(options.part).len() as u64;
+
+    let progress: Vec<_> = solutions
+        .iter()
+        .map(|solution| {
+            let bar = ProgressBar::new(steps)
+                .with_style(style.clone())
+                .with_prefix(format!("Day {}", solution.day()));
+            bars.add(bar)
+        })
+        .collect();
+
+    let runs = pool.install(|| {
+        solutions
+            .par_iter()
+            .zip(&progress)
+            .map(|(solution, bar)| {
+                let run = run_day(*solution, options, bar);
+                bar.finish_with_message("done");
+                run
+            })
+            .collect()
+    });
+
+    bars.clear().ok();
+    Ok(runs)
+}
+
+fn print_error(message: impl std::fmt::Display) {
+    eprintln!("{}error:{} {}", ANSI_BOLD, ANSI_RESET, message);
+}
+
+/// Prints the answers of a day, up to the first step that failed.
+fn print_day(run: &DayRun) {
+    if let Err(DayError::Load(_)) = run.parts {
+        return;
+    }
+
+    println!("----");
+    println!();
+    println!("{}Day {}: {}{}", ANSI_BOLD, run.solution.day(), run.solution.title(), ANSI_RESET);
+
+    let Ok(parts) = &run.parts else {
+        return;
+    };
+
+    println!("{}(parsed in {:.2?}){}", ANSI_ITALIC, run.parse, ANSI_RESET);
+
+    for part in parts {
+        println!();
+        println!("🎄 {}Part {}{} 🎄", ANSI_BOLD, part.part.number(), ANSI_RESET);
+        println!();
+
+        let Ok(answer) = &part.answer else {
+            return;
+        };
+
+        println!("{} {}(elapsed: {:.2?}){}", answer, ANSI_ITALIC, part.elapsed, ANSI_RESET);
+    }
+
+    println!();
+    println!("----");
+}
+
+/// Prints why a day failed, returns whether it failed at all.
+fn print_failure(run: &DayRun) -> bool {
+    let day = run.solution.day();
+
+    let parts = match &run.parts {
+        Ok(parts) => parts,
+        Err(DayError::Load(err)) => {
+            print_error(err);
+            return true;
+        }
+        Err(DayError::Parse(err)) => {
+            eprintln!();
+            print_error(format!("could not parse the input for day {}", day));
+            eprintln!("{}", err.render(&run.input));
+            return true;
+        }
+    };
+
+    let failed = parts
+        .iter()
+        .find_map(|part| part.answer.as_ref().err().map(|err| (part.part, err)));
+
+    match failed {
+        Some((part, err)) => {
+            eprintln!();
+            print_error(format!("could not solve part {} of day {}: {}", part.number(), day, err));
+            true
+        }
+        None => false,
+    }
+}
+
+fn day_timings(run: &DayRun) -> Option<DayTimings> {
+    let mut parts = [None; 2];
+
+    for part in run.parts.as_ref().ok()? {
+        parts[part.part.number() as usize - 1] = Some(part.elapsed);
+    }
+
+    Some(DayTimings {
+        day: run.solution.day(),
+        title: run.solution.title(),
+        load: run.load,
+        parse: run.parse,
+        parts,
+    })
+}
+
+fn day_records(run: &DayRun, part: Option<Part>) -> Vec<Record> {
+    let day = run.solution.day();
+
+    let message = match &run.parts {
+        Ok(parts) => {
+            return parts
+                .iter()
+                .map(|part| Record {
+                    day,
+                    part: part.part.number(),
+                    result: part.answer.clone().map_err(|err| err.to_string()),
+                    elapsed: part.elapsed,
+                })
+                .collect()
+        }
+        Err(DayError::Load(err)) => err.to_string(),
+        Err(DayError::Parse(err)) => format!("could not parse the input: {}", err),
+    };
+
+    selected_parts(part)
+        .into_iter()
+        .map(|part| Record {
+            day,
+            part: part.number(),
+            result: Err(message.clone()),
+            elapsed: Duration::ZERO,
+        })
+        .collect()
+}
+
+/// Writes the runs for `--format json|csv`.
+///
+/// Unlike the text output this keeps going after a failed day, failures end
+/// up in the records and only change the exit code.
+fn write_records(options: &RunOptions, runs: impl Iterator<Item = DayRun>) -> ExitCode {
+    let records: Vec<_> = runs.flat_map(|run| day_records(&run, options.part)).collect();
+
+    let out: Box<dyn Write> = match &options.output {
+        Some(path) => match File::create(path) {
+            Ok(file) => Box::new(BufWriter::new(file)),
+            Err(err) => {
+                print_error(format!("could not create {}: {}", path.display(), err));
+                return ExitCode::FAILURE;
+            }
+        },
+        None => Box::new(io::stdout().lock()),
+    };
+
+    let result = match options.format {
+        Format::Json => output::write_json(&records, out),
+        Format::Csv => output::write_csv(&records, out),
+        Format::Text => unreachable!(),
+    };
+
+    if let Err(err) = result {
+        print_error(format!("could not write the results: {}", err));
+        return ExitCode::FAILURE;
+    }
+
+    if records.iter().any(|record| record.result.is_err()) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn run(options: RunOptions) -> ExitCode {
+    let solutions = match options.days.select() {
+        Ok(solutions) => solutions,
+        Err(err) => {
+            print_error(err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Sequential runs are lazy, so the answers show up as soon as they are
+    // known and the timings are not skewed by other days running alongside.
+    let hidden = ProgressBar::hidden();
+    let runs: Box<dyn Iterator<Item = DayRun>> = if options.jobs > 1 {
+        match run_parallel(&solutions, &options) {
+            Ok(runs) => Box::new(runs.into_iter()),
+            Err(err) => {
+                print_error(format!("could not start {} threads: {}", options.jobs, err));
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        Box::new(
+            solutions
+                .into_iter()
+                .map(|solution| run_day(solution, &options, &hidden)),
+        )
+    };
+
+    if options.format != Format::Text {
+        return write_records(&options, runs);
+    }
+
+    let mut timings = Vec::new();
+
+    for run in runs {
+        if !options.timings {
+            print_day(&run);
+        }
+
+        if print_failure(&run) {
+            return ExitCode::FAILURE;
+        }
+
+        if options.timings {
+            timings.extend(day_timings(&run));
+        }
+    }
+
+    if !options.timings {
+        return ExitCode::SUCCESS;
+    }
+
+    let report = Report::new(timings);
+    println!("{}", report);
+
+    match options.budget {
+        Some(budget) if report.total() > budget => {
+            eprintln!();
+            print_error(format!(
+                "total runtime of {:.2?} exceeds the budget of {:.2?}",
+                report.total(),
+                budget
+            ));
+            ExitCode::FAILURE
+        }
+        _ => ExitCode::SUCCESS,
+    }
+}
+
+fn fetch(options: FetchOptions) -> ExitCode {
+    let cache = Cache::new(HttpProvider::from_env(), input::search_dirs()[0].join("inputs"));
+
+    for day in options.days.numbers() {
+        match cache.get(day, options.force) {
+            Ok(fetched) if fetched.cached => {
+                println!("Day {}: already cached in {}", day, fetched.path.display());
+            }
+            Ok(fetched) => println!("Day {}: saved to {}", day, fetched.path.display()),
+            Err(err) => {
+                print_error(err);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn verify(options: VerifyOptions) -> ExitCode {
+    let path = options.answers.unwrap_or_else(answers::default_path);
+
+    let mut answers = match Answers::load(&path) {
+        Ok(answers) => answers,
+        Err(err) => {
+            print_error(format!("could not load {}: {}", path.display(), err));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let solutions = match options.days.select() {
+        Ok(solutions) => solutions,
+        Err(err) => {
+            print_error(err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (mut matched, mut failed, mut unknown, mut recorded) = (0, 0, 0, 0);
+
+    println!("{:>3}  {:>4}  {:<16}  {:<16}  Status", "Day", "Part", "Answer", "Expected");
+
+    for solution in solutions {
+        let day = solution.day();
+
+        let input = match input::load("inputs", day) {
+            Ok(input) => input,
+            Err(_) => {
+                println!("{:>3}  {:>4}  {:<16}  {:<16}  no input", day, "-", "-", "-");
+                unknown += 2;
+                continue;
+            }
+        };
+
+        let input = match solution.parse(&input) {
+            Ok(input) => input,
+            Err(err) => {
+                println!("{:>3}  {:>4}  {:<16}  {:<16}  error: {}", day, "-", "-", "-", err);
+                failed += 2;
+                continue;
+            }
+        };
+
+        for part in [Part::One, Part::Two] {
+            let answer = match solve_part(solution, &input, part) {
+                Ok(answer) => answer,
+                Err(err) => {
+                    println!("{:>3}  {:>4}  {:<16}  {:<16}  error: {}", day, part.number(), "-", "-", err);
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            let (expected, status) = match answers.check(day, part.number(), &answer) {
+                Verdict::Match => {
+                    matched += 1;
+                    (answer.to_string(), "ok")
+                }
+                Verdict::Mismatch(expected) => {
+                    failed += 1;
+                    (expected.to_string(), "MISMATCH")
+                }
+                Verdict::Unknown if options.record => {
+                    recorded += 1;
+                    answers.insert(day, part.number(), answer.clone());
+                    ("-".to_string(), "recorded")
+                }
+                Verdict::Unknown => {
+                    unknown += 1;
+                    ("-".to_string(), "unknown")
+                }
+            };
+
+            println!("{:>3}  {:>4}  {:<16}  {:<16}  {}", day, part.number(), answer, expected, status);
+        }
+    }
+
+    println!();
+    println!("{} ok, {} failed, {} unknown, {} recorded", matched, failed, unknown, recorded);
+
+    if recorded > 0 {
+        if let Err(err) = answers.save(&path) {
+            print_error(format!("could not write {}: {}", path.display(), err));
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn new(options: NewOptions) -> ExitCode {
+    match scaffold::create(&scaffold::default_dir(), options.day, &options.title) {
+        Ok(scaffold) => {
+            for path in scaffold.created {
+                println!("created {}", path.display());
+            }
+
+            for path in scaffold.kept {
+                println!("kept {}", path.display());
+            }
+
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            print_error(err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Loads the input of `day` from `source` and parses it with `parse`, printing
+/// what went wrong if either fails.
+fn load_parsed<T>(
+    source: &InputSource,
+    day: u8,
+    parse: impl FnOnce(&str) -> Result<T, ParseError>,
+) -> Option<T> {
+    let input = match load_input(source, day) {
+        Ok(input) => input,
+        Err(err) => {
+            print_error(err);
+            return None;
+        }
+    };
+
+    match parse(&input) {
+        Ok(parsed) => Some(parsed),
+        Err(err) => {
+            print_error(format!("could not parse the input for day {}", day));
+            eprintln!("{}", err.render(&input));
+            None
+        }
+    }
+}
+
+fn crucible(options: CrucibleOptions) -> ExitCode {
+    let Some(grid) = load_parsed(&options.input, 17, day17::parse) else {
+        return ExitCode::FAILURE;
+    };
+
+    let Some(route) = day17::find_route(&grid, options.rules) else {
+        print_error("there is no route to the factory for this crucible");
+        return ExitCode::FAILURE;
+    };
+
+    if options.route {
+        println!("{}", route.render(&grid));
+        println!();
+    }
+
+    println!("heat loss: {}", route.heat_loss);
+    ExitCode::SUCCESS
+}
+
+fn trace(options: TraceOptions) -> ExitCode {
+    let Some(circuit) = load_parsed(&options.input, 20, day20::parse) else {
+        return ExitCode::FAILURE;
+    };
+
+    let path = &options.output;
+    let written = File::create(path)
+        .and_then(|file| day20::write_trace(&circuit, options.presses, BufWriter::new(file)));
+
+    match written {
+        Ok(pulses) => {
+            println!(
+                "wrote {} pulses of {} presses to {}",
+                pulses,
+                options.presses,
+                path.display()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            print_error(format!("could not write {}: {}", path.display(), err));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn graph(options: GraphOptions) -> ExitCode {
+    let Some(circuit) = load_parsed(&options.input, 20, day20::parse) else {
+        return ExitCode::FAILURE;
+    };
+
+    let path = &options.output;
+
+    match fs::write(path, circuit.to_dot()) {
+        Ok(()) => {
+            println!("wrote the circuit to {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            print_error(format!("could not write {}: {}", path.display(), err));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs `f` `runs` times and returns the median time.
+fn median<T, E>(runs: usize, mut f: impl FnMut() -> Result<T, E>) -> Result<Duration, E> {
+    let mut times = Vec::with_capacity(runs);
+
+    for _ in 0..runs {
+        let timer = Instant::now();
+        f()?;
+        times.push(timer.elapsed());
+    }
+
+    times.sort();
+    Ok(times[times.len() / 2])
+}
+
+fn measure(solution: &dyn Solution, input: &str, runs: usize) -> Result<[Duration; 3], String> {
+    let day = solution.day();
+    let parse_error = |err: ParseError| format!("could not parse the input for day {}: {}", day, err);
+
+    let parse = median(runs, || solution.parse(input)).map_err(parse_error)?;
+    let input = solution.parse(input).map_err(parse_error)?;
+
+    let mut steps = [parse, Duration::ZERO, Duration::ZERO];
+
+    for part in [Part::One, Part::Two] {
+        steps[part.number() as usize] = median(runs, || solve_part(solution, &input, part))
+            .map_err(|err| format!("could not solve part {} of day {}: {}", part.number(), day, err))?;
+    }
+
+    Ok(steps)
+}
+
+fn bench(options: BenchOptions) -> ExitCode {
+    let solutions = match options.days.select() {
+        Ok(solutions) => solutions,
+        Err(err) => {
+            print_error(err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let before = match &options.compare {
+        Some(name) => {
+            let path = baseline::path(name).unwrap();
+
+            match Baseline::load(&path) {
+                Ok(before) => Some(before),
+                Err(err) => {
+                    print_error(format!("could not load the baseline `{}`: {}", name, err));
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        None => None,
+    };
+
+    let mut current = Baseline::default();
+
+    for solution in solutions {
+        let day = solution.day();
+
+        let steps = load_input(&InputSource::Puzzle, day)
+            .map_err(|err| err.to_string())
+            .and_then(|input| measure(solution, &input, options.runs));
+
+        match steps {
+            Ok(steps) => {
+                println!(
+                    "Day {:>2}: {} {:.2?}, {} {:.2?}, {} {:.2?}",
+                    day, STEPS[0], steps[0], STEPS[1], steps[1], STEPS[2], steps[2]
+                );
+                current.insert(day, steps);
+            }
+            Err(err) => {
+                print_error(err);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if let Some(name) = &options.save {
+        let path = baseline::path(name).unwrap();
+
+        match current.save(&path) {
+            Ok(()) => println!("\nSaved the baseline `{}` to {}", name, path.display()),
+            Err(err) => {
+                print_error(format!("could not save {}: {}", path.display(), err));
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let Some(before) = before else {
+        return ExitCode::SUCCESS;
+    };
+
+    let comparison = before.compare(&current);
+    println!();
+    println!("{}", comparison);
+
+    let threshold = options.threshold as f64;
+    let regressions = comparison.regressions(threshold).count();
+
+    if regressions > 0 {
+        eprintln!();
+        print_error(format!(
+            "{} of {} steps got more than {}% slower than the baseline `{}`",
+            regressions,
+            comparison.changes().len(),
+            options.threshold,
+            options.compare.unwrap()
+        ));
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn main() -> ExitCode {
+    let command = match cli::parse_args(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(err) => {
+            print_error(err);
+            eprintln!();
+            eprintln!("{}", cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match command {
+        Command::Run(options) => run(options),
+        Command::Fetch(options) => fetch(options),
+        Command::Verify(options) => verify(options),
+        Command::New(options) => new(options),
+        Command::Bench(options) => bench(options),
+        Command::Crucible(options) => crucible(options),
+        Command::Trace(options) => trace(options),
+        Command::Graph(options) => graph(options),
+        Command::List => {
+            for solution in SOLUTIONS {
+                println!("{:>2}  {}", solution.day(), solution.title());
+            }
+
+            ExitCode::SUCCESS
+        }
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
+    }
+}
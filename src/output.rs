@@ -0,0 +1,204 @@
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
+
+use crate::Answer;
+
+/// The outcome of running a single part, as written by `aoc run --format`.
+///
+/// `result` holds the error message if the input could not be loaded or
+/// parsed, or if the part failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub result: Result<Answer, String>,
+    pub elapsed: Duration,
+}
+
+impl Record {
+    pub fn status(&self) -> &'static str {
+        match self.result {
+            Ok(_) => "ok",
+            Err(_) => "error",
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+fn json_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Text(s) => json_string(s),
+        answer => answer.to_string(),
+    }
+}
+
+/// Writes the records as a JSON array with one object per line.
+///
+/// Numeric answers are written as JSON numbers, text answers as strings.
+/// `answer` is `null` for failed parts and `error` is `null` otherwise.
+pub fn write_json(records: &[Record], mut out: impl Write) -> io::Result<()> {
+    writeln!(out, "[")?;
+
+    for (idx, record) in records.iter().enumerate() {
+        let (answer, error) = match &record.result {
+            Ok(answer) => (json_answer(answer), "null".to_string()),
+            Err(err) => ("null".to_string(), json_string(err)),
+        };
+
+        let separator = if idx + 1 < records.len() { "," } else { "" };
+
+        writeln!(
+            out,
+            "  {{\"day\": {}, \"part\": {}, \"answer\": {}, \"elapsed_ns\": {}, \"status\": \"{}\", \"error\": {}}}{}",
+            record.day,
+            record.part,
+            answer,
+            record.elapsed.as_nanos(),
+            record.status(),
+            error,
+            separator
+        )?;
+    }
+
+    writeln!(out, "]")?;
+    out.flush()
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Writes the records as CSV with a header line.
+///
+/// The `answer` column is empty for failed parts, `error` is empty otherwise.
+pub fn write_csv(records: &[Record], mut out: impl Write) -> io::Result<()> {
+    writeln!(out, "day,part,answer,elapsed_ns,status,error")?;
+
+    for record in records {
+        let (answer, error) = match &record.result {
+            Ok(answer) => (csv_field(&answer.to_string()), String::new()),
+            Err(err) => (String::new(), csv_field(err)),
+        };
+
+        writeln!(
+            out,
+            "{},{},{},{},{},{}",
+            record.day,
+            record.part,
+            answer,
+            record.elapsed.as_nanos(),
+            record.status(),
+            error
+        )?;
+    }
+
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 1,
+                part: 1,
+                result: Ok(54968.into()),
+                elapsed: Duration::from_micros(12),
+            },
+            Record {
+                day: 1,
+                part: 2,
+                result: Ok("a \"b\", c".into()),
+                elapsed: Duration::from_nanos(5),
+            },
+            Record {
+                day: 2,
+                part: 1,
+                result: Err("line 1, column 2: invalid \"x\"".to_string()),
+                elapsed: Duration::ZERO,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_json() {
+        let mut out = Vec::new();
+        write_json(&records(), &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            r#"[
+  {"day": 1, "part": 1, "answer": 54968, "elapsed_ns": 12000, "status": "ok", "error": null},
+  {"day": 1, "part": 2, "answer": "a \"b\", c", "elapsed_ns": 5, "status": "ok", "error": null},
+  {"day": 2, "part": 1, "answer": null, "elapsed_ns": 0, "status": "error", "error": "line 1, column 2: invalid \"x\""}
+]
+"#
+        );
+
+        let mut out = Vec::new();
+        write_json(&[], &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "[\n]\n");
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut out = Vec::new();
+        write_csv(&records(), &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            r#"day,part,answer,elapsed_ns,status,error
+1,1,54968,12000,ok,
+1,2,"a ""b"", c",5,ok,
+2,1,,0,error,"line 1, column 2: invalid ""x"""
+"#
+        );
+    }
+
+    /// Accepts everything but fails once the data has to be written out.
+    struct Full;
+
+    impl Write for Full {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::StorageFull.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_errors() {
+        // The records fit into the buffer, so only the final flush fails.
+        assert!(write_json(&records(), io::BufWriter::new(Full)).is_err());
+        assert!(write_csv(&records(), io::BufWriter::new(Full)).is_err());
+    }
+}
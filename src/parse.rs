@@ -0,0 +1,149 @@
+use std::{error::Error, fmt, str::FromStr};
+
+/// An error in a puzzle input, pointing at the text that could not be parsed.
+///
+/// `line` and `column` are 1-based and count characters, so they can be used
+/// directly to underline the offending text in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Creates an error for `fragment`, which has to be a slice of `input`.
+    ///
+    /// The position is derived from where `fragment` lives inside `input`, so
+    /// parsers only need to keep slices of the original input around instead
+    /// of tracking line and column numbers themselves.
+    pub fn at(input: &str, fragment: &str, message: impl Into<String>) -> Self {
+        let start = input.as_ptr() as usize;
+        let offset = (fragment.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|offset| offset + fragment.len() <= input.len())
+            .or_else(|| input.find(fragment))
+            .unwrap_or(input.len());
+
+        let before = &input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = before[line_start..].chars().count() + 1;
+
+        Self {
+            line,
+            column,
+            text: fragment.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Creates an error for input that ended before something required was found.
+    pub fn end_of_input(input: &str, message: impl Into<String>) -> Self {
+        Self::at(input, &input[input.len()..], message)
+    }
+
+    /// Renders the error together with the offending line of `input`, with the
+    /// parsed text underlined.
+    pub fn render(&self, input: &str) -> String {
+        let source = input.lines().nth(self.line - 1).unwrap_or_default();
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+
+        let remaining = source.chars().count().saturating_sub(self.column - 1);
+        let width = self.text.lines().next().map_or(0, |t| t.chars().count());
+
+        format!(
+            "{}\n{} |\n{} | {}\n{} | {}{}",
+            self,
+            gutter,
+            number,
+            source,
+            gutter,
+            " ".repeat(self.column - 1),
+            "^".repeat(width.min(remaining).max(1)),
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)?;
+
+        if !self.text.is_empty() {
+            write!(f, " `{}`", self.text)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses `fragment`, a slice of `input`, as a number.
+pub fn number<T: FromStr>(input: &str, fragment: &str) -> Result<T, ParseError> {
+    fragment
+        .parse()
+        .map_err(|_| ParseError::at(input, fragment, "invalid number"))
+}
+
+/// Splits `fragment`, a slice of `input`, at the first occurrence of `delimiter`.
+pub fn split_once<'a>(
+    input: &str,
+    fragment: &'a str,
+    delimiter: &str,
+) -> Result<(&'a str, &'a str), ParseError> {
+    fragment
+        .split_once(delimiter)
+        .ok_or_else(|| ParseError::at(input, fragment, format!("expected `{}` in", delimiter)))
+}
+
+/// Returns the single character starting at byte `idx` of `fragment` as a slice.
+pub fn char_at(fragment: &str, idx: usize) -> &str {
+    let len = fragment[idx..].chars().next().map_or(0, char::len_utf8);
+    &fragment[idx..idx + len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        let input = "32T3K 765\nT55J5 684\nKK6X7 28";
+        let line = input.lines().nth(2).unwrap();
+
+        let err = ParseError::at(input, &line[3..4], "invalid card");
+        assert_eq!((err.line, err.column), (3, 4));
+        assert_eq!(err.to_string(), "line 3, column 4: invalid card `X`");
+
+        let err = ParseError::end_of_input(input, "missing bet");
+        assert_eq!((err.line, err.column), (3, 9));
+    }
+
+    #[test]
+    fn test_render() {
+        let input = "32T3K 765\nKK6X7 28";
+        let err = ParseError::at(input, &input[10..15], "invalid hand");
+
+        assert_eq!(
+            err.render(input),
+            "line 2, column 1: invalid hand `KK6X7`\n  |\n2 | KK6X7 28\n  | ^^^^^"
+        );
+    }
+
+    #[test]
+    fn test_helpers() {
+        let input = "Game 1: 3 blue\nGame x: 4 red";
+        let (game, _) = split_once(input, input.lines().nth(1).unwrap(), ": ").unwrap();
+
+        let err = number::<usize>(input, &game[5..]).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 6));
+
+        let err = split_once(input, &input[..6], "x").unwrap_err();
+        assert_eq!(err.to_string(), "line 1, column 1: expected `x` in `Game 1`");
+
+        assert_eq!(char_at("aµb", 1), "µ");
+    }
+}
@@ -0,0 +1,227 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The module every new day starts from.
+const TEMPLATE: &str = include_str!("solutions/example.rs");
+
+/// The day `solutions/example.rs` reads its example input for.
+const TEMPLATE_DAY: &str = "25";
+
+/// The expected answers of a new example, filled in once they are known.
+fn expected_answers(day: u8) -> String {
+    format!("[day{:02}]\n# part1 = 0\n# part2 = 0\n", day)
+}
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    /// The day already has a module or an entry in the registry.
+    Exists(u8, PathBuf),
+    /// `solutions/mod.rs` does not contain the `solutions!` list.
+    MissingRegistry(PathBuf),
+    /// Reading or writing a file failed.
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaffoldError::Exists(day, path) => {
+                write!(f, "day {} already exists in {}", day, path.display())
+            }
+            ScaffoldError::MissingRegistry(path) => {
+                write!(f, "could not find the `solutions!` list in {}", path.display())
+            }
+            ScaffoldError::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+/// The files touched by [`create`].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Scaffold {
+    /// Files that were written.
+    pub created: Vec<PathBuf>,
+    /// Input files that already existed and were left alone.
+    pub kept: Vec<PathBuf>,
+}
+
+/// The default source folder, `src` of the crate.
+pub fn default_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src")
+}
+
+/// The module for `day`, generated from `solutions/example.rs`.
+pub fn render(day: u8) -> String {
+    let from = format!("read_example({}, ", TEMPLATE_DAY);
+    let to = format!("read_example({}, ", day);
+
+    TEMPLATE.replace(&from, &to)
+}
+
+/// Adds `day` to the `solutions!` list in `registry`, keeping the list sorted.
+///
+/// Returns `None` if there is no `solutions!` list.
+fn register(registry: &str, day: u8, title: &str) -> Option<String> {
+    let module = format!("day{:02}", day);
+    let title = title.replace('\\', "\\\\").replace('"', "\\\"");
+    let entry = format!("    {} => Day{:02}, {}, \"{}\";\n", module, day, day, title);
+
+    let start = registry.find("\nsolutions! {\n")? + "\nsolutions! {\n".len();
+    let end = start + registry[start..].find("\n}")? + 1;
+
+    let mut at = end;
+    let mut offset = start;
+
+    for line in registry[start..end].split_inclusive('\n') {
+        if line.trim_start() > module.as_str() {
+            at = offset;
+            break;
+        }
+
+        offset += line.len();
+    }
+
+    let mut registry = registry.to_string();
+    registry.insert_str(at, &entry);
+    Some(registry)
+}
+
+fn is_registered(registry: &str, day: u8) -> bool {
+    let module = format!("day{:02} =>", day);
+    registry.lines().any(|line| line.trim_start().starts_with(&module))
+}
+
+/// Creates the module, the example and input placeholders and the registry
+/// entry for `day` inside the source folder `src`.
+///
+/// The example goes to `examples/dayNN/example.txt`, with its expected
+/// answers commented out in `example.toml` next to it.
+///
+/// Refuses to touch anything if the module or the registry entry already
+/// exist. Input files that are already there, e.g. because they were fetched
+/// before, are kept.
+pub fn create(src: &Path, day: u8, title: &str) -> Result<Scaffold, ScaffoldError> {
+    let module = src.join("solutions").join(format!("day{:02}.rs", day));
+    let registry_path = src.join("solutions").join("mod.rs");
+
+    if module.exists() {
+        return Err(ScaffoldError::Exists(day, module));
+    }
+
+    let registry = fs::read_to_string(&registry_path)
+        .map_err(|err| ScaffoldError::Io(registry_path.clone(), err))?;
+
+    if is_registered(&registry, day) {
+        return Err(ScaffoldError::Exists(day, registry_path));
+    }
+
+    let registry = register(&registry, day, title)
+        .ok_or_else(|| ScaffoldError::MissingRegistry(registry_path.clone()))?;
+
+    let mut scaffold = Scaffold::default();
+
+    fs::write(&module, render(day)).map_err(|err| ScaffoldError::Io(module.clone(), err))?;
+    scaffold.created.push(module);
+
+    let examples = src.join("examples").join(format!("day{:02}", day));
+    let placeholders = [
+        (examples.join("example.txt"), String::new()),
+        (examples.join("example.toml"), expected_answers(day)),
+        (src.join("inputs").join(format!("day{:02}.txt", day)), String::new()),
+    ];
+
+    for (path, contents) in placeholders {
+        if path.exists() {
+            scaffold.kept.push(path);
+            continue;
+        }
+
+        let dir = path.parent().unwrap();
+        fs::create_dir_all(dir).map_err(|err| ScaffoldError::Io(dir.to_path_buf(), err))?;
+        fs::write(&path, contents).map_err(|err| ScaffoldError::Io(path.clone(), err))?;
+        scaffold.created.push(path);
+    }
+
+    fs::write(&registry_path, registry)
+        .map_err(|err| ScaffoldError::Io(registry_path.clone(), err))?;
+    scaffold.created.push(registry_path);
+
+    Ok(scaffold)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::answers::Answers;
+
+    use super::*;
+
+    const REGISTRY: &str = "\
+use crate::Solution;
+
+solutions! {
+    day01 => Day01, 1, \"Trebuchet?!\";
+    day03 => Day03, 3, \"Gear Ratios\";
+}
+
+pub fn find() {}
+";
+
+    #[test]
+    fn test_render() {
+        let module = render(7);
+
+        assert!(module.contains("read_example(7, \"example\")"));
+        assert!(!module.contains("read_example(25, "));
+    }
+
+    #[test]
+    fn test_register() {
+        let registry = register(REGISTRY, 2, "Cube \"Conundrum\"").unwrap();
+        assert!(registry.contains(
+            "    day01 => Day01, 1, \"Trebuchet?!\";\n    \
+             day02 => Day02, 2, \"Cube \\\"Conundrum\\\"\";\n    \
+             day03 => Day03, 3, \"Gear Ratios\";\n}"
+        ));
+
+        let registry = register(REGISTRY, 25, "Day 25").unwrap();
+        assert!(registry.contains("    day25 => Day25, 25, \"Day 25\";\n}\n\npub fn find"));
+
+        assert!(is_registered(REGISTRY, 3));
+        assert!(!is_registered(REGISTRY, 2));
+        assert_eq!(register("fn main() {}", 2, "Day 2"), None);
+    }
+
+    #[test]
+    fn test_create() {
+        let src = env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&src);
+        fs::create_dir_all(src.join("solutions")).unwrap();
+        fs::create_dir_all(src.join("inputs")).unwrap();
+        fs::write(src.join("solutions/mod.rs"), REGISTRY).unwrap();
+        fs::write(src.join("inputs/day02.txt"), "fetched").unwrap();
+
+        let scaffold = create(&src, 2, "Cube Conundrum").unwrap();
+        assert_eq!(scaffold.created.len(), 4);
+        assert_eq!(scaffold.kept, [src.join("inputs/day02.txt")]);
+
+        assert_eq!(fs::read_to_string(src.join("solutions/day02.rs")).unwrap(), render(2));
+        assert_eq!(fs::read_to_string(src.join("examples/day02/example.txt")).unwrap(), "");
+        assert_eq!(
+            Answers::parse(&fs::read_to_string(src.join("examples/day02/example.toml")).unwrap()),
+            Ok(Answers::default())
+        );
+        assert_eq!(fs::read_to_string(src.join("inputs/day02.txt")).unwrap(), "fetched");
+        assert!(is_registered(&fs::read_to_string(src.join("solutions/mod.rs")).unwrap(), 2));
+
+        assert!(matches!(create(&src, 2, "Again"), Err(ScaffoldError::Exists(2, _))));
+        assert!(matches!(create(&src, 3, "Registered"), Err(ScaffoldError::Exists(3, _))));
+
+        fs::remove_dir_all(src).unwrap();
+    }
+}
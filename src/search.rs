@@ -0,0 +1,315 @@
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, VecDeque},
+    hash::Hash,
+};
+
+use num::Zero;
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+
+/// The result of a search that reached a goal.
+///
+/// Keeps the parent of every state that was reached, so the path to the goal
+/// can be rebuilt with [`Found::path`] if it is needed.
+#[derive(Debug, Clone)]
+pub struct Found<S, C> {
+    /// The total cost from the start to the goal, the number of steps for
+    /// [`bfs`].
+    pub cost: C,
+    pub goal: S,
+    parents: HashMap<S, (C, Option<S>)>,
+}
+
+impl<S: Clone + Eq + Hash, C> Found<S, C> {
+    /// The states from the start to the goal, both included.
+    pub fn path(&self) -> Vec<S> {
+        let mut path = vec![self.goal.clone()];
+
+        while let Some((_, Some(parent))) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+
+        path.reverse();
+        path
+    }
+}
+
+/// Breadth-first search from `start` to the closest state matching `is_goal`.
+pub fn bfs<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut parents = HashMap::default();
+    let mut queue = VecDeque::new();
+
+    parents.insert(start.clone(), (0, None));
+    queue.push_back((start, 0));
+
+    while let Some((state, steps)) = queue.pop_front() {
+        if is_goal(&state) {
+            return Some(Found {
+                cost: steps,
+                goal: state,
+                parents,
+            });
+        }
+
+        for next in successors(&state) {
+            if let Entry::Vacant(entry) = parents.entry(next.clone()) {
+                entry.insert((steps + 1, Some(state.clone())));
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Every state reachable from `start` with its distance in steps, in
+/// breadth-first order.
+///
+/// Useful for flood fills, where there is no goal and every state is needed.
+pub fn bfs_reach<S, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+) -> impl Iterator<Item = (S, usize)>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut seen = HashSet::default();
+    bfs_reach_with(start, successors, move |state: &S| seen.insert(state.clone()))
+}
+
+/// Like [`bfs_reach`], but `visit` keeps track of the states that were seen.
+///
+/// `visit` is called for every state found and returns whether it is new,
+/// like [`HashSet::insert`]. States that map to a small index can use a
+/// bitset instead of hashing.
+pub fn bfs_reach_with<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut visit: impl FnMut(&S) -> bool,
+) -> impl Iterator<Item = (S, usize)>
+where
+    I: IntoIterator<Item = S>,
+{
+    let mut queue = VecDeque::new();
+
+    visit(&start);
+    queue.push_back((start, 0));
+
+    std::iter::from_fn(move || {
+        let (state, steps) = queue.pop_front()?;
+
+        for next in successors(&state) {
+            if visit(&next) {
+                queue.push_back((next, steps + 1));
+            }
+        }
+
+        Some((state, steps))
+    })
+}
+
+/// Dijkstra's algorithm from `start` to the cheapest state matching `is_goal`.
+///
+/// `successors` returns the next states together with the cost of moving to
+/// them, which must not be negative.
+pub fn dijkstra<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, successors, |_| C::zero(), is_goal)
+}
+
+/// A* search from `start` to the cheapest state matching `is_goal`.
+///
+/// `heuristic` estimates the remaining cost to a goal. The result is only
+/// guaranteed to be the cheapest if it never overestimates.
+pub fn astar<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut parents: HashMap<S, (C, Option<S>)> = HashMap::default();
+    let mut open = BinaryHeap::new();
+
+    parents.insert(start.clone(), (C::zero(), None));
+    open.push(Open {
+        estimate: heuristic(&start),
+        cost: C::zero(),
+        state: start,
+    });
+
+    while let Some(Open { cost, state, .. }) = open.pop() {
+        // A cheaper way to this state was found after it was queued.
+        if parents[&state].0 < cost {
+            continue;
+        }
+
+        if is_goal(&state) {
+            return Some(Found {
+                cost,
+                goal: state,
+                parents,
+            });
+        }
+
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+
+            match parents.entry(next.clone()) {
+                Entry::Occupied(entry) if entry.get().0 <= next_cost => continue,
+                Entry::Occupied(mut entry) => {
+                    entry.insert((next_cost, Some(state.clone())));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert((next_cost, Some(state.clone())));
+                }
+            }
+
+            open.push(Open {
+                estimate: next_cost + heuristic(&next),
+                cost: next_cost,
+                state: next,
+            });
+        }
+    }
+
+    None
+}
+
+/// A state waiting in the open set of [`astar`], ordered so the heap pops the
+/// lowest estimate first.
+struct Open<S, C> {
+    estimate: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> Ord for Open<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&other.estimate, &other.cost).cmp(&(&self.estimate, &self.cost))
+    }
+}
+
+impl<S, C: Ord> PartialOrd for Open<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> PartialEq for Open<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S, C: Ord> Eq for Open<S, C> {}
+
+#[cfg(test)]
+mod tests {
+    use grid::Grid;
+
+    use crate::geom::Point;
+    use crate::grid::parse_grid_with;
+
+    use super::*;
+
+    const MAZE: &str = "\
+S.#.....
+.##.###.
+....#...
+.##...#E";
+
+    fn maze() -> (Grid<bool>, Point, Point) {
+        let parsed = parse_grid_with(MAZE, MAZE, &['S', 'E'], |c| match c {
+            '#' => Ok(false),
+            '.' | 'S' | 'E' => Ok(true),
+            _ => Err("invalid cell"),
+        })
+        .unwrap();
+
+        let start = parsed.marker('S').unwrap().into();
+        let goal = parsed.marker('E').unwrap().into();
+        (parsed.grid, start, goal)
+    }
+
+    fn open_neighbours(grid: &Grid<bool>, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point
+            .neighbours(grid)
+            .map(|(_, next)| next)
+            .filter(|&next| grid[next])
+    }
+
+    #[test]
+    fn test_bfs() {
+        let (grid, start, goal) = maze();
+
+        let found = bfs(start, |&p| open_neighbours(&grid, p), |&p| p == goal).unwrap();
+        assert_eq!(found.cost, 12);
+
+        let path = found.path();
+        assert_eq!(path.len(), 13);
+        assert_eq!((path[0], path[12]), (start, goal));
+        assert!(path.windows(2).all(|w| w[0].manhattan(w[1]) == 1));
+
+        assert!(bfs(start, |&p| open_neighbours(&grid, p), |&p| p == Point::new(0, 2)).is_none());
+    }
+
+    #[test]
+    fn test_bfs_reach() {
+        let (grid, start, goal) = maze();
+
+        let reached: HashMap<_, _> = bfs_reach(start, |&p| open_neighbours(&grid, p)).collect();
+        assert_eq!(reached.len(), grid.iter().filter(|&&open| open).count());
+        assert_eq!(reached[&start], 0);
+        assert_eq!(reached[&goal], 12);
+
+        let mut seen = Grid::new(grid.rows(), grid.cols());
+        let visit = |&p: &Point| !std::mem::replace(&mut seen[p], true);
+        let order: Vec<_> = bfs_reach_with(start, |&p| open_neighbours(&grid, p), visit).collect();
+        assert_eq!(order.len(), reached.len());
+        assert!(order.iter().all(|(p, steps)| reached[p] == *steps));
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        let (grid, start, goal) = maze();
+
+        // Moving down costs 5, everything else 1.
+        let successors = |&p: &Point| {
+            open_neighbours(&grid, p)
+                .map(move |next| (next, if next.y > p.y { 5 } else { 1 }))
+                .collect::<Vec<_>>()
+        };
+
+        let found = dijkstra(start, successors, |&p| p == goal).unwrap();
+        assert_eq!(found.cost, 28);
+        assert_eq!(found.path().first(), Some(&start));
+
+        let found = astar(start, successors, |p| p.manhattan(goal), |&p| p == goal).unwrap();
+        assert_eq!(found.cost, 28);
+        assert_eq!(found.path().last(), Some(&goal));
+
+        assert!(dijkstra(start, successors, |&p| p == Point::new(0, 2)).is_none());
+    }
+}
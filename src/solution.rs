@@ -0,0 +1,43 @@
+use std::{any::Any, error::Error, fmt};
+
+use crate::{Answer, ParseError};
+
+/// The parsed input of a day, as returned by [`Solution::parse`].
+///
+/// Every day parses into its own `Input` type, which is hidden behind `Any` so
+/// all days fit into the same registry. Only the day that parsed it can use it.
+pub type Parsed = Box<dyn Any>;
+
+/// An input that parsed fine but that a part cannot compute an answer for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveError(pub String);
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Error for SolveError {}
+
+/// A single day of the calendar.
+///
+/// Every `solutions::dayNN` module is wired into [`crate::solutions::SOLUTIONS`]
+/// through an implementation of this trait, so the binary, the benches and the
+/// tests can all iterate over the same list of days.
+///
+/// The input is parsed once with [`Solution::parse`] and then shared by both
+/// parts, which keeps parsing out of the part timings.
+pub trait Solution: Sync {
+    fn day(&self) -> u8;
+
+    fn title(&self) -> &'static str;
+
+    fn parse(&self, input: &str) -> Result<Parsed, ParseError>;
+
+    /// Solves part one, `input` has to come from `parse` of the same day.
+    fn part_one(&self, input: &Parsed) -> Result<Answer, SolveError>;
+
+    /// Solves part two, `input` has to come from `parse` of the same day.
+    fn part_two(&self, input: &Parsed) -> Result<Answer, SolveError>;
+}
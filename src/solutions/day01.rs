@@ -1,4 +1,6 @@
-fn get_digits_part1(s: &str) -> (u8, u8) {
+use crate::{Answer, ParseError, SolveError};
+
+fn get_digits_part1(s: &str) -> Option<(u8, u8)> {
     let mut first = None;
     let mut last = None;
 
@@ -12,7 +14,7 @@ fn get_digits_part1(s: &str) -> (u8, u8) {
         }
     }
 
-    (first.unwrap(), last.unwrap())
+    Some((first?, last?))
 }
 
 fn end_matches(window: u64, s: &str) -> bool {
@@ -29,7 +31,7 @@ fn end_matches(window: u64, s: &str) -> bool {
     (window & mask) == s_val
 }
 
-fn get_digits_part2(s: &str) -> (u8, u8) {
+fn get_digits_part2(s: &str) -> Option<(u8, u8)> {
     let mut first = None;
     let mut last = None;
 
@@ -71,50 +73,67 @@ fn get_digits_part2(s: &str) -> (u8, u8) {
         }
     }
 
-    (first.unwrap(), last.unwrap())
+    Some((first?, last?))
 }
 
-pub fn part_one(input: &str) -> u32 {
-    input
-        .lines()
-        .map(|line| {
-            let (f, l) = get_digits_part1(line);
-            f as u32 * 10 + l as u32
-        })
-        .sum()
+/// The calibration document, one entry per line.
+pub type Input = Vec<String>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    Ok(input.lines().map(str::to_string).collect())
 }
 
-pub fn part_two(input: &str) -> u32 {
-    input
-        .lines()
-        .map(|line| {
-            let (f, l) = get_digits_part2(line);
-            f as u32 * 10 + l as u32
+fn calibration_sum(
+    lines: &Input,
+    get_digits: impl Fn(&str) -> Option<(u8, u8)>,
+) -> Result<Answer, SolveError> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            let (f, l) = get_digits(line)
+                .ok_or_else(|| SolveError(format!("no digit in line {}", idx + 1)))?;
+            Ok(f as u32 * 10 + l as u32)
         })
-        .sum()
+        .sum::<Result<u32, _>>()
+        .map(Answer::from)
+}
+
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    calibration_sum(input, get_digits_part1)
+}
+
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    calibration_sum(input, get_digits_part2)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{read_file, read_file_with_name};
+    use crate::{read_example, read_file};
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file_with_name("examples", "01_1");
-        assert_eq!(part_one(&input), 142);
+        let input = parse(&read_example(1, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 142);
+
+        let input = parse(&read_file("inputs", 1)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 54968);
 
-        let input = read_file("inputs", 1);
-        assert_eq!(part_one(&input), 54968);
+        let input = parse(&read_example(1, "example2")).unwrap();
+        assert_eq!(
+            part_one(&input).unwrap_err().to_string(),
+            "no digit in line 2"
+        );
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file_with_name("examples", "01_2");
-        assert_eq!(part_two(&input), 281);
+        let input = parse(&read_example(1, "example2")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 281);
 
-        let input = read_file("inputs", 1);
-        assert_eq!(part_two(&input), 54094);
+        let input = parse(&read_file("inputs", 1)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 54094);
     }
 }
@@ -1,7 +1,10 @@
 use std::cmp::max;
 
-#[derive(Default)]
-struct Observation {
+use crate::parse::{number, split_once};
+use crate::{Answer, ParseError, SolveError};
+
+#[derive(Debug, Default)]
+pub struct Observation {
     red: usize,
     green: usize,
     blue: usize,
@@ -11,95 +14,105 @@ const MAX_RED: usize = 12;
 const MAX_GREEN: usize = 13;
 const MAX_BLUE: usize = 14;
 
-fn parse_game(game: &str) -> (usize, impl Iterator<Item = Observation> + '_) {
+fn parse_game(input: &str, game: &str) -> Result<(usize, Vec<Observation>), ParseError> {
     //Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
 
-    let (game_id, contents) = game.split_once(": ").expect("Cant parse game line");
+    let (game_id, contents) = split_once(input, game, ": ")?;
     let id = game_id
         .strip_prefix("Game ")
-        .expect("Game id malformed")
-        .parse::<usize>()
-        .expect("Invalid game id");
-
-    let iter = contents.split("; ").map(move |single_game| {
-        let mut observation = Observation::default();
-
-        for item in single_game.split(", ") {
-            let (count_str, color) = item.split_once(' ').expect("Invalid item");
-            let count = count_str.parse::<usize>().expect("Invalid item count");
-            match color {
-                "red" => observation.red = count,
-                "green" => observation.green = count,
-                "blue" => observation.blue = count,
-                _ => panic!("Invalid color"),
+        .ok_or_else(|| ParseError::at(input, game_id, "game id malformed"))?;
+    let id = number(input, id)?;
+
+    let observations = contents
+        .split("; ")
+        .map(|single_game| {
+            let mut observation = Observation::default();
+
+            for item in single_game.split(", ") {
+                let (count_str, color) = split_once(input, item, " ")?;
+                let count = number(input, count_str)?;
+                match color {
+                    "red" => observation.red = count,
+                    "green" => observation.green = count,
+                    "blue" => observation.blue = count,
+                    _ => return Err(ParseError::at(input, color, "invalid color")),
+                }
             }
-        }
 
-        observation
-    });
+            Ok(observation)
+        })
+        .collect::<Result<_, _>>()?;
 
-    (id, iter)
+    Ok((id, observations))
 }
 
-fn get_max_min_counts(iter: impl Iterator<Item = Observation>) -> Observation {
-    iter.reduce(|mut acc, item| {
+/// The id of every game together with the cubes shown in each round.
+pub type Input = Vec<(usize, Vec<Observation>)>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    input.lines().map(|game| parse_game(input, game)).collect()
+}
+
+fn get_max_min_counts<'a>(iter: impl Iterator<Item = &'a Observation>) -> Observation {
+    iter.fold(Observation::default(), |mut acc, item| {
         acc.red = max(acc.red, item.red);
         acc.green = max(acc.green, item.green);
         acc.blue = max(acc.blue, item.blue);
         acc
     })
-    .unwrap_or_default()
 }
 
-pub fn part_one(input: &str) -> u32 {
-    input
-        .lines()
-        .map(parse_game)
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    let sum = input
+        .iter()
         .filter_map(|(id, items)| {
-            let max_counts = get_max_min_counts(items);
+            let max_counts = get_max_min_counts(items.iter());
 
             if max_counts.red <= MAX_RED
                 && max_counts.green <= MAX_GREEN
                 && max_counts.blue <= MAX_BLUE
             {
-                Some(id)
+                Some(*id)
             } else {
                 None
             }
         })
-        .sum::<usize>() as u32
+        .sum::<usize>();
+
+    Ok(sum.into())
 }
 
-pub fn part_two(input: &str) -> u32 {
-    input
-        .lines()
-        .map(parse_game)
-        .map(|(_, items)| get_max_min_counts(items))
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    let sum = input
+        .iter()
+        .map(|(_, items)| get_max_min_counts(items.iter()))
         .map(|counts| counts.red * counts.green * counts.blue)
-        .sum::<usize>() as u32
+        .sum::<usize>();
+
+    Ok(sum.into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::{read_example, read_file};
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 2);
-        assert_eq!(part_one(&input), 8);
+        let input = parse(&read_example(2, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 8);
 
-        let input = read_file("inputs", 2);
-        assert_eq!(part_one(&input), 2795)
+        let input = parse(&read_file("inputs", 2)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 2795)
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 2);
-        assert_eq!(part_two(&input), 2286);
+        let input = parse(&read_example(2, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 2286);
 
-        let input = read_file("inputs", 2);
-        assert_eq!(part_two(&input), 75561);
+        let input = parse(&read_file("inputs", 2)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 75561);
     }
 }
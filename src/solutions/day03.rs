@@ -2,6 +2,8 @@ use std::cmp::{max, min};
 
 use smallvec::SmallVec;
 
+use crate::{Answer, ParseError, SolveError};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct Coord {
     x: usize,
@@ -17,12 +19,13 @@ struct RawBoard {
     size: usize,
 }
 
-struct Board {
+/// The numbers of the schematic and the numbers adjacent to each symbol.
+pub struct Board {
     numbers: Vec<u32>,
     adjacent_numbers_to_symbols: Vec<(char, SmallVec<[NumberIdx; 6]>)>,
 }
 
-fn parse_input(input: &str) -> RawBoard {
+fn parse_schematic(input: &str) -> Result<RawBoard, ParseError> {
     let mut symbols = Vec::new();
     let mut numbers = Vec::new();
     let size = input.lines().count();
@@ -37,6 +40,14 @@ fn parse_input(input: &str) -> RawBoard {
         };
 
     for (y, line) in input.lines().enumerate() {
+        if line.chars().count() != size {
+            return Err(ParseError::at(
+                input,
+                line,
+                format!("expected a square schematic with {} columns", size),
+            ));
+        }
+
         let mut current_number = None;
 
         for (x, c) in line.chars().enumerate() {
@@ -66,21 +77,23 @@ fn parse_input(input: &str) -> RawBoard {
         end_current_number(&mut numbers, &mut current_number);
     }
 
-    RawBoard {
+    Ok(RawBoard {
         digits,
         numbers,
         symbols,
         size,
-    }
+    })
 }
 
-fn process_board(input: &str) -> Board {
+pub type Input = Board;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
     let RawBoard {
         digits,
         numbers,
         symbols,
         size,
-    } = parse_input(input);
+    } = parse_schematic(input)?;
 
     // build up adjacency map for symbols
     let mut adjacent_numbers_to_symbols = Vec::new();
@@ -112,44 +125,46 @@ fn process_board(input: &str) -> Board {
         }
     }
 
-    Board {
+    Ok(Board {
         adjacent_numbers_to_symbols,
         numbers,
-    }
+    })
 }
 
-pub fn part_one(input: &str) -> u32 {
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
     let Board {
         numbers,
         adjacent_numbers_to_symbols,
-    } = process_board(input);
+    } = input;
 
     let mut is_part_number = vec![false; numbers.len()];
 
-    for (_, numbers) in adjacent_numbers_to_symbols.into_iter() {
-        for start_cord in numbers {
+    for (_, numbers) in adjacent_numbers_to_symbols {
+        for &start_cord in numbers {
             is_part_number[start_cord as usize] = true;
         }
     }
 
-    is_part_number
+    let sum = is_part_number
         .into_iter()
         .enumerate()
         .filter(|(_, is_part)| *is_part)
         .map(|(c, _)| numbers.get(c).unwrap())
-        .sum()
+        .sum::<u32>();
+
+    Ok(sum.into())
 }
 
-pub fn part_two(input: &str) -> u32 {
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
     let Board {
         numbers,
         adjacent_numbers_to_symbols,
-    } = process_board(input);
+    } = input;
 
     let mut total_gear_ratios = 0;
 
-    for (c, adjacent_numbers) in adjacent_numbers_to_symbols.into_iter() {
-        if c != '*' || adjacent_numbers.len() != 2 {
+    for (c, adjacent_numbers) in adjacent_numbers_to_symbols {
+        if *c != '*' || adjacent_numbers.len() != 2 {
             continue;
         }
 
@@ -161,30 +176,30 @@ pub fn part_two(input: &str) -> u32 {
         total_gear_ratios += gear_ratio;
     }
 
-    total_gear_ratios
+    Ok(total_gear_ratios.into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::{read_example, read_file};
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 3);
-        assert_eq!(part_one(&input), 4361);
+        let input = parse(&read_example(3, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 4361);
 
-        let input = read_file("inputs", 3);
-        assert_eq!(part_one(&input), 557705);
+        let input = parse(&read_file("inputs", 3)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 557705);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 3);
-        assert_eq!(part_two(&input), 467835);
+        let input = parse(&read_example(3, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 467835);
 
-        let input = read_file("inputs", 3);
-        assert_eq!(part_two(&input), 84266818);
+        let input = parse(&read_file("inputs", 3)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 84266818);
     }
 }
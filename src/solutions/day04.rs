@@ -1,73 +1,91 @@
 use bitvec::array::BitArray;
 use std::{cmp::min, ops::BitAnd};
 
-fn get_winning_count(card: &str) -> usize {
-    let (_, numbers) = card.split_once(':').expect("invalid input");
-    let (winning_numbers, card_numbers) = numbers.split_once('|').expect("invalid input");
+use crate::parse::{number, split_once};
+use crate::{Answer, ParseError, SolveError};
+
+fn get_winning_count(input: &str, card: &str) -> Result<usize, ParseError> {
+    let (_, numbers) = split_once(input, card, ":")?;
+    let (winning_numbers, card_numbers) = split_once(input, numbers, "|")?;
 
     let parse_numbers = |numbers: &str| {
         let mut bits: BitArray<[usize; 2]> = BitArray::ZERO;
 
         for n in numbers.split_ascii_whitespace() {
-            let n = n.parse::<usize>().unwrap();
-            bits.set(n, true);
+            let idx = number::<usize>(input, n)?;
+
+            if idx >= bits.len() {
+                return Err(ParseError::at(input, n, "number out of range"));
+            }
+
+            bits.set(idx, true);
         }
 
-        bits
+        Ok(bits)
     };
 
-    let winning_numbers = parse_numbers(winning_numbers);
-    let card_numbers = parse_numbers(card_numbers);
+    let winning_numbers = parse_numbers(winning_numbers)?;
+    let card_numbers = parse_numbers(card_numbers)?;
 
-    winning_numbers.bitand(card_numbers).count_ones()
+    Ok(winning_numbers.bitand(card_numbers).count_ones())
 }
 
-pub fn part_one(input: &str) -> u32 {
-    input.lines().map(get_winning_count).fold(0, |acc, count| {
+/// The number of winning numbers on each card.
+pub type Input = Vec<usize>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    input
+        .lines()
+        .map(|card| get_winning_count(input, card))
+        .collect()
+}
+
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    let points = input.iter().fold(0u32, |acc, &count| {
         acc + match count {
             0 => 0,
             m => 1 << (m - 1),
         }
-    })
+    });
+
+    Ok(points.into())
 }
 
-pub fn part_two(input: &str) -> u32 {
-    let win_counts = input.lines().map(get_winning_count).collect::<Vec<_>>();
-    let mut instances = vec![1; win_counts.len()];
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    let mut instances = vec![1u32; input.len()];
 
-    win_counts
-        .into_iter()
-        .enumerate()
-        .fold(0, |acc, (i, count)| {
-            for k in (i + 1)..min(i + 1 + count, instances.len()) {
-                instances[k] += instances[i];
-            }
+    let total = input.iter().enumerate().fold(0, |acc, (i, &count)| {
+        for k in (i + 1)..min(i + 1 + count, instances.len()) {
+            instances[k] += instances[i];
+        }
+
+        acc + instances[i]
+    });
 
-            acc + instances[i]
-        })
+    Ok(total.into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::{read_example, read_file};
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 4);
-        assert_eq!(part_one(&input), 13);
+        let input = parse(&read_example(4, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 13);
 
-        let input = read_file("inputs", 4);
-        assert_eq!(part_one(&input), 26443);
+        let input = parse(&read_file("inputs", 4)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 26443);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 4);
-        assert_eq!(part_two(&input), 30);
+        let input = parse(&read_example(4, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 30);
 
-        let input = read_file("inputs", 4);
-        assert_eq!(part_two(&input), 6284877);
+        let input = parse(&read_file("inputs", 4)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 6284877);
     }
 }
@@ -1,5 +1,8 @@
 use std::cmp::min;
 
+use crate::parse::number;
+use crate::{Answer, ParseError, SolveError};
+
 #[derive(Debug)]
 struct Map {
     dst: usize,
@@ -8,53 +11,67 @@ struct Map {
 }
 
 impl Map {
-    fn from_str(input: &str) -> Self {
-        let mut parts = input.split(' ');
+    fn parse(input: &str, line: &str) -> Result<Self, ParseError> {
+        let mut parts = line.split(' ');
 
-        let mut parse_next_number = || parts.next().unwrap().parse().unwrap();
+        let mut parse_next_number = || match parts.next() {
+            Some(n) => number(input, n),
+            None => Err(ParseError::at(input, line, "expected three numbers in")),
+        };
 
-        let dst = parse_next_number();
-        let src = parse_next_number();
-        let len = parse_next_number();
+        let dst = parse_next_number()?;
+        let src = parse_next_number()?;
+        let len = parse_next_number()?;
 
-        Map { dst, src, len }
+        Ok(Map { dst, src, len })
     }
 }
 
+/// The seeds and the maps of the almanac, in the order they are applied.
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     maps: Vec<Vec<Map>>,
     input_numbers: Vec<usize>,
 }
 
-fn parse_input(input: &str) -> Input {
+pub fn parse(input: &str) -> Result<Input, ParseError> {
     let sections: Vec<&str> = input.split("\n\n").collect();
     let mut maps: Vec<Vec<Map>> = Vec::new();
     let mut input_numbers: Vec<usize> = Vec::new();
 
     for section in sections {
         if let Some(raw_input_numbers) = section.strip_prefix("seeds: ") {
-            for n in raw_input_numbers.split(' ').map(|n| n.parse().unwrap()) {
-                input_numbers.push(n);
+            for n in raw_input_numbers.split(' ') {
+                input_numbers.push(number(input, n)?);
             }
             continue;
         }
 
-        let map_section: Vec<Map> = section.lines().skip(1).map(Map::from_str).collect();
+        let map_section: Vec<Map> = section
+            .lines()
+            .skip(1)
+            .map(|line| Map::parse(input, line))
+            .collect::<Result<_, _>>()?;
         maps.push(map_section);
     }
 
-    Input {
+    if input_numbers.is_empty() {
+        return Err(ParseError::end_of_input(input, "no seeds"));
+    }
+
+    Ok(Input {
         maps,
         input_numbers,
-    }
+    })
 }
 
-pub fn part_one(input: &str) -> u32 {
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
     let Input {
         maps,
-        mut input_numbers,
-    } = parse_input(input);
+        input_numbers,
+    } = input;
+
+    let mut input_numbers = input_numbers.clone();
 
     for section in maps {
         for number in input_numbers.iter_mut() {
@@ -66,16 +83,22 @@ pub fn part_one(input: &str) -> u32 {
         }
     }
 
-    input_numbers.into_iter().min().unwrap() as u32
+    Ok(input_numbers.into_iter().min().unwrap().into())
 }
 
-pub fn part_two(input: &str) -> u32 {
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
     let Input {
         maps,
         input_numbers,
-    } = parse_input(input);
+    } = input;
+
+    let seeds = input_numbers.chunks_exact(2);
+
+    if !seeds.remainder().is_empty() {
+        return Err(SolveError("seed ranges need an even number of seeds".to_string()));
+    }
 
-    let mut ranges: Vec<(usize, usize)> = input_numbers.chunks(2).map(|c| (c[0], c[1])).collect();
+    let mut ranges: Vec<(usize, usize)> = seeds.map(|c| (c[0], c[1])).collect();
 
     for section in maps {
         let mut next_ranges = Vec::new();
@@ -100,30 +123,30 @@ pub fn part_two(input: &str) -> u32 {
         ranges = next_ranges;
     }
 
-    ranges.into_iter().map(|(s, _)| s).min().unwrap() as u32
+    Ok(ranges.into_iter().map(|(s, _)| s).min().unwrap().into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::{read_example, read_file};
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 5);
-        assert_eq!(part_one(&input), 35);
+        let input = parse(&read_example(5, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 35);
 
-        let input = read_file("inputs", 5);
-        assert_eq!(part_one(&input), 403695602);
+        let input = parse(&read_file("inputs", 5)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 403695602);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 5);
-        assert_eq!(part_two(&input), 46);
+        let input = parse(&read_example(5, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 46);
 
-        let input = read_file("inputs", 5);
-        assert_eq!(part_two(&input), 219529182);
+        let input = parse(&read_file("inputs", 5)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 219529182);
     }
 }
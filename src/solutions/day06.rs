@@ -1,89 +1,149 @@
+use crate::parse::{char_at, number};
+use crate::{Answer, ParseError, SolveError};
+
 fn get_number_of_strategies(time: u64, distance: u64) -> u64 {
-    let square = (time * time - 4 * (distance + 1)) as f64;
-    let root = square.sqrt();
+    // Without a real root, no button press beats the record.
+    let Some(square) = (time as u128 * time as u128).checked_sub(4 * (distance as u128 + 1)) else {
+        return 0;
+    };
+
+    let root = (square as f64).sqrt();
     let min = (0.5 * (time as f64 - root)).ceil() as u64;
     let max = (0.5 * (time as f64 + root)).floor() as u64;
 
-    max - min + 1
+    (max + 1).saturating_sub(min)
+}
+
+fn next_line<'a>(
+    input: &str,
+    lines: &mut impl Iterator<Item = &'a str>,
+    name: &str,
+) -> Result<&'a str, ParseError> {
+    let line = lines
+        .next()
+        .ok_or_else(|| ParseError::end_of_input(input, format!("missing `{}` line", name)))?;
+
+    line.strip_prefix(name)
+        .ok_or_else(|| ParseError::at(input, line, format!("expected `{}` in", name)))
 }
 
-fn parse_input(input: &str) -> impl Iterator<Item = (u64, u64)> + '_ {
+fn parse_races(input: &str) -> Result<Vec<(u64, u64)>, ParseError> {
     let mut lines = input.lines();
 
-    let mut parse_line = |name: &str| {
-        lines
-            .next()
-            .unwrap()
-            .strip_prefix(name)
-            .unwrap()
+    let mut parse_line = |name: &str| -> Result<(&str, Vec<u64>), ParseError> {
+        let line = next_line(input, &mut lines, name)?;
+        let numbers = line
             .split_ascii_whitespace()
-            .map(|n| n.parse().unwrap())
+            .map(|n| number(input, n))
+            .collect::<Result<_, _>>()?;
+
+        Ok((line, numbers))
     };
 
-    let times = parse_line("Time: ");
-    let distances = parse_line("Distance: ");
+    let (_, times) = parse_line("Time: ")?;
+    let (line, distances) = parse_line("Distance: ")?;
 
-    times.zip(distances)
-}
+    if times.len() != distances.len() {
+        let message = format!("{} distances for {} times in", distances.len(), times.len());
+        return Err(ParseError::at(input, line, message));
+    }
 
-pub fn part_one(input: &str) -> u32 {
-    parse_input(input)
-        .map(|(t, d)| get_number_of_strategies(t, d))
-        .product::<u64>() as u32
+    Ok(times.into_iter().zip(distances).collect())
 }
 
-fn parse_input_2(input: &str) -> (u64, u64) {
+fn parse_single_race(input: &str) -> Result<(u64, u64), ParseError> {
     let mut lines = input.lines();
 
-    let mut parse_line = |name: &str| -> u64 {
-        let line = lines.next().unwrap().strip_prefix(name).unwrap();
+    let mut parse_line = |name: &str| -> Result<u64, ParseError> {
+        let line = next_line(input, &mut lines, name)?;
 
         let mut n: u64 = 0;
 
-        for c in line.chars() {
+        for (i, c) in line.char_indices() {
             if c.is_ascii_whitespace() {
                 continue;
             }
 
+            let digit = c
+                .to_digit(10)
+                .ok_or_else(|| ParseError::at(input, char_at(line, i), "invalid digit"))?;
+
             n *= 10;
-            n += c.to_digit(10).unwrap() as u64;
+            n += digit as u64;
         }
 
-        n
+        Ok(n)
     };
 
-    let times = parse_line("Time: ");
-    let distances = parse_line("Distance: ");
+    let times = parse_line("Time: ")?;
+    let distances = parse_line("Distance: ")?;
 
-    (times, distances)
+    Ok((times, distances))
 }
 
-pub fn part_two(input: &str) -> u32 {
-    let (times, distance) = parse_input_2(input);
-    get_number_of_strategies(times, distance) as u32
+/// The races, read as separate numbers and with the spaces removed.
+#[derive(Debug)]
+pub struct Input {
+    races: Vec<(u64, u64)>,
+    race: (u64, u64),
+}
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    Ok(Input {
+        races: parse_races(input)?,
+        race: parse_single_race(input)?,
+    })
+}
+
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    let product = input
+        .races
+        .iter()
+        .map(|&(t, d)| get_number_of_strategies(t, d))
+        .product::<u64>();
+
+    Ok(product.into())
+}
+
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    let (time, distance) = input.race;
+    Ok(get_number_of_strategies(time, distance).into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::{read_example, read_file};
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 6);
-        assert_eq!(part_one(&input), 288);
+        let input = parse(&read_example(6, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 288);
 
-        let input = read_file("inputs", 6);
-        assert_eq!(part_one(&input), 4811940);
+        let input = parse(&read_file("inputs", 6)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 4811940);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 6);
-        assert_eq!(part_two(&input), 71503);
+        let input = parse(&read_example(6, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 71503);
+
+        let input = parse(&read_file("inputs", 6)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 30077773);
+    }
 
-        let input = read_file("inputs", 6);
-        assert_eq!(part_two(&input), 30077773);
+    #[test]
+    fn test_unwinnable() {
+        let input = parse("Time: 0 4\nDistance: 0 4").unwrap();
+        assert_eq!(part_one(&input).unwrap(), 0);
+        assert_eq!(get_number_of_strategies(4, 3), 1);
+
+        let err = parse("Time: 7 15\nDistance: 9").unwrap_err();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (2, "1 distances for 2 times in")
+        );
     }
 }
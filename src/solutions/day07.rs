@@ -1,7 +1,10 @@
 use std::cmp::Ordering;
 
+use crate::parse::{char_at, number, split_once};
+use crate::{Answer, ParseError, SolveError};
+
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, PartialOrd, Ord)]
-enum Card {
+pub enum Card {
     Two,
     Three,
     Four,
@@ -18,22 +21,22 @@ enum Card {
 }
 
 impl Card {
-    fn from_char(s: char) -> Card {
+    fn from_char(s: char) -> Option<Card> {
         match s {
-            '2' => Card::Two,
-            '3' => Card::Three,
-            '4' => Card::Four,
-            '5' => Card::Five,
-            '6' => Card::Six,
-            '7' => Card::Seven,
-            '8' => Card::Eight,
-            '9' => Card::Nine,
-            'T' => Card::Ten,
-            'J' => Card::Jack,
-            'Q' => Card::Queen,
-            'K' => Card::King,
-            'A' => Card::Ace,
-            _ => panic!("invalid card: {}", s),
+            '2' => Some(Card::Two),
+            '3' => Some(Card::Three),
+            '4' => Some(Card::Four),
+            '5' => Some(Card::Five),
+            '6' => Some(Card::Six),
+            '7' => Some(Card::Seven),
+            '8' => Some(Card::Eight),
+            '9' => Some(Card::Nine),
+            'T' => Some(Card::Ten),
+            'J' => Some(Card::Jack),
+            'Q' => Some(Card::Queen),
+            'K' => Some(Card::King),
+            'A' => Some(Card::Ace),
+            _ => None,
         }
     }
 }
@@ -183,67 +186,83 @@ impl Hand {
     }
 }
 
-fn parse_input(inpuit: &str) -> impl Iterator<Item = ([Card; 5], usize)> + '_ {
-    inpuit.lines().map(|line| {
-        let (cards, bet) = line.split_once(' ').expect("invalid format");
+/// Every hand with its bet.
+pub type Input = Vec<([Card; 5], usize)>;
 
-        assert_eq!(cards.len(), 5, "invalid number of cards");
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            let (cards, bet) = split_once(input, line, " ")?;
 
-        let mut parsed_cards = [Card::Two; 5];
+            if cards.chars().count() != 5 {
+                return Err(ParseError::at(input, cards, "expected 5 cards"));
+            }
 
-        for (i, card) in cards.chars().enumerate() {
-            parsed_cards[i] = Card::from_char(card);
-        }
+            let mut parsed_cards = [Card::Two; 5];
+
+            for (i, (idx, card)) in cards.char_indices().enumerate() {
+                parsed_cards[i] = Card::from_char(card)
+                    .ok_or_else(|| ParseError::at(input, char_at(cards, idx), "invalid card"))?;
+            }
 
-        let bet = bet.parse().expect("invalid bet");
+            let bet = number(input, bet)?;
 
-        (parsed_cards, bet)
-    })
+            Ok((parsed_cards, bet))
+        })
+        .collect()
 }
 
-fn solve(input: &str, with_jokers: bool) -> u32 {
-    let mut input: Vec<_> = parse_input(input)
-        .map(|(cards, bet)| Hand::new(cards, bet, with_jokers))
+fn solve(input: &Input, with_jokers: bool) -> usize {
+    let mut hands: Vec<_> = input
+        .iter()
+        .map(|&(cards, bet)| Hand::new(cards, bet, with_jokers))
         .collect();
 
-    input.sort();
+    hands.sort();
 
-    input
+    hands
         .into_iter()
         .enumerate()
         .map(|(i, hand)| (i + 1) * hand.bet)
-        .sum::<usize>() as u32
+        .sum()
 }
 
-pub fn part_one(input: &str) -> u32 {
-    solve(input, false)
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    Ok(solve(input, false).into())
 }
 
-pub fn part_two(input: &str) -> u32 {
-    solve(input, true)
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    Ok(solve(input, true).into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::{read_example, read_file};
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 7);
-        assert_eq!(part_one(&input), 6440);
+        let input = parse(&read_example(7, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 6440);
 
-        let input = read_file("inputs", 7);
-        assert_eq!(part_one(&input), 252656917);
+        let input = parse(&read_file("inputs", 7)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 252656917);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 7);
-        assert_eq!(part_two(&input), 5905);
+        let input = parse(&read_example(7, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 5905);
 
-        let input = read_file("inputs", 7);
-        assert_eq!(part_two(&input), 253499763);
+        let input = parse(&read_file("inputs", 7)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 253499763);
+    }
+
+    #[test]
+    fn test_invalid_card() {
+        let err = parse("32T3K 765\nKK6X7 28").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 4, "X"));
     }
 }
@@ -3,61 +3,76 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 use rustc_hash::FxHashMap as HashMap;
 
-#[derive(Debug)]
-enum Direction {
-    Left,
-    Right,
-}
-
-impl Direction {
-    fn from_char(d: char) -> Self {
-        match d {
-            'L' => Direction::Left,
-            'R' => Direction::Right,
-            _ => panic!("invalid direction: {}", d),
-        }
-    }
-}
+use crate::geom::Turn;
+use crate::parse::char_at;
+use crate::{Answer, ParseError, SolveError};
 
 static NODE_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^(\w{3}) = \((\w{3}), (\w{3})\)$").unwrap());
 
-struct Input<'a> {
-    directions: Vec<Direction>,
-    graph: HashMap<&'a str, (&'a str, &'a str)>,
+/// The directions and the left and right neighbour of every node.
+pub struct Input {
+    directions: Vec<Turn>,
+    graph: HashMap<String, (String, String)>,
 }
 
-fn parse_input(input: &'_ str) -> Input<'_> {
+pub fn parse(input: &str) -> Result<Input, ParseError> {
     let mut lines = input.lines();
 
-    let directions: Vec<_> = lines
+    let directions_line = lines
         .next()
-        .expect("directions missing")
-        .chars()
-        .map(Direction::from_char)
-        .collect();
+        .ok_or_else(|| ParseError::end_of_input(input, "directions missing"))?;
+
+    let directions = directions_line
+        .char_indices()
+        .map(|(i, d)| {
+            Turn::try_from(d).map_err(|_| {
+                ParseError::at(input, char_at(directions_line, i), "invalid direction")
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
     lines.next();
 
-    let mut graph = HashMap::default();
+    let mut nodes = Vec::new();
 
     for line in lines {
-        let cap = NODE_RE.captures(line).expect("invalid format");
+        let cap = NODE_RE
+            .captures(line)
+            .ok_or_else(|| ParseError::at(input, line, "invalid node"))?;
 
         let origin = cap.get(1).unwrap().as_str();
         let left = cap.get(2).unwrap().as_str();
         let right = cap.get(3).unwrap().as_str();
 
-        graph.insert(origin, (left, right));
+        nodes.push((origin, left, right));
+    }
+
+    let graph: HashMap<_, _> = nodes
+        .iter()
+        .map(|&(origin, left, right)| (origin.to_string(), (left.to_string(), right.to_string())))
+        .collect();
+
+    for &(_, left, right) in &nodes {
+        for target in [left, right] {
+            if !graph.contains_key(target) {
+                return Err(ParseError::at(input, target, "unknown node"));
+            }
+        }
     }
 
-    Input { directions, graph }
+    Ok(Input { directions, graph })
 }
 
-pub fn part_one(input: &str) -> u32 {
-    let Input { directions, graph } = parse_input(input);
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    let Input { directions, graph } = input;
+
+    if !graph.contains_key("AAA") {
+        return Err(SolveError("there is no node AAA".to_string()));
+    }
+
     let mut currect_node = "AAA";
 
-    let mut steps = 0;
+    let mut steps = 0u32;
 
     for d in directions.iter().cycle() {
         if currect_node == "ZZZ" {
@@ -66,23 +81,23 @@ pub fn part_one(input: &str) -> u32 {
 
         steps += 1;
 
-        let (left, right) = graph[currect_node];
+        let (left, right) = &graph[currect_node];
         match d {
-            Direction::Left => {
+            Turn::Left => {
                 currect_node = left;
             }
-            Direction::Right => {
+            Turn::Right => {
                 currect_node = right;
             }
         }
     }
 
-    steps
+    Ok(steps.into())
 }
 
 fn solve_single_node(
-    graph: &HashMap<&str, (&str, &str)>,
-    directions: &[Direction],
+    graph: &HashMap<String, (String, String)>,
+    directions: &[Turn],
     node: &str,
 ) -> usize {
     let mut current_node = node;
@@ -96,13 +111,13 @@ fn solve_single_node(
 
         steps += 1;
 
-        let (left, right) = graph[current_node];
+        let (left, right) = &graph[current_node];
 
         match d {
-            Direction::Left => {
+            Turn::Left => {
                 current_node = left;
             }
-            Direction::Right => {
+            Turn::Right => {
                 current_node = right;
             }
         }
@@ -111,31 +126,32 @@ fn solve_single_node(
     steps
 }
 
-pub fn part_two(input: &str) -> usize {
-    let Input { directions, graph } = parse_input(input);
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    let Input { directions, graph } = input;
 
-    graph
+    Ok(graph
         .keys()
         .filter(|name| name.ends_with('A'))
-        .map(|node| solve_single_node(&graph, &directions, node))
+        .map(|node| solve_single_node(graph, directions, node))
         .fold(1, lcm)
+        .into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{read_file, read_file_with_name};
+    use crate::read_example;
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 8);
-        assert_eq!(part_one(&input), 6);
+        let input = parse(&read_example(8, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 6);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file_with_name("examples", "08_2");
-        assert_eq!(part_two(&input), 6);
+        let input = parse(&read_example(8, "example2")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 6);
     }
 }
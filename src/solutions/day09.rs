@@ -1,18 +1,29 @@
-fn parse_input(input: &str) -> impl Iterator<Item = Vec<i64>> + '_ {
-    input.lines().map(|line| {
-        line.split_ascii_whitespace()
-            .map(|s| s.parse().unwrap())
-            .collect()
-    })
+use crate::parse::number;
+use crate::{Answer, ParseError, SolveError};
+
+/// The history of every value.
+pub type Input = Vec<Vec<i64>>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            line.split_ascii_whitespace()
+                .map(|s| number(input, s))
+                .collect()
+        })
+        .collect()
 }
 
 fn differentiate(input: &[i64]) -> Vec<i64> {
     input.windows(2).map(|w| w[1] - w[0]).collect()
 }
 
-pub fn part_one(input: &str) -> i64 {
-    parse_input(input)
-        .map(|mut measurement| {
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    let sum = input
+        .iter()
+        .map(|measurement| {
+            let mut measurement = measurement.clone();
             let mut last_values = Vec::new();
 
             loop {
@@ -28,12 +39,16 @@ pub fn part_one(input: &str) -> i64 {
                 .into_iter()
                 .rfold(0, |last_change, v| v + last_change)
         })
-        .sum()
+        .sum::<i64>();
+
+    Ok(sum.into())
 }
 
-pub fn part_two(input: &str) -> i64 {
-    parse_input(input)
-        .map(|mut measurement| {
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    let sum = input
+        .iter()
+        .map(|measurement| {
+            let mut measurement = measurement.clone();
             let mut first_values = Vec::new();
 
             loop {
@@ -49,30 +64,32 @@ pub fn part_two(input: &str) -> i64 {
                 .into_iter()
                 .rfold(0, |last_change, v| v - last_change)
         })
-        .sum()
+        .sum::<i64>();
+
+    Ok(sum.into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::{read_example, read_file};
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 9);
-        assert_eq!(part_one(&input), 114);
+        let input = parse(&read_example(9, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 114);
 
-        let input = read_file("inputs", 9);
-        assert_eq!(part_one(&input), 1637452029);
+        let input = parse(&read_file("inputs", 9)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 1637452029);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 9);
-        assert_eq!(part_two(&input), 2);
+        let input = parse(&read_example(9, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 2);
 
-        let input = read_file("inputs", 9);
-        assert_eq!(part_two(&input), 908);
+        let input = parse(&read_file("inputs", 9)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 908);
     }
 }
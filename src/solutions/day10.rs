@@ -1,6 +1,10 @@
-use std::collections::VecDeque;
 use grid::Grid;
 
+use crate::geom::{Direction4, Point};
+use crate::grid::parse_grid_with;
+use crate::search::bfs_reach_with;
+use crate::{Answer, ParseError, SolveError};
+
 #[derive(Debug)]
 enum Pipe {
     Vertical,
@@ -12,154 +16,104 @@ enum Pipe {
     Starting,
 }
 
-#[derive(Debug)]
-enum Port {
-    North,
-    East,
-    South,
-    West,
-}
+impl TryFrom<char> for Pipe {
+    type Error = &'static str;
 
-impl Port {
-    fn offset(&self) -> (isize, isize) {
-        match self {
-            Self::North => (-1, 0),
-            Self::East => (0, 1),
-            Self::South => (1, 0),
-            Self::West => (0, -1),
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '|' => Ok(Self::Vertical),
+            '-' => Ok(Self::Horizontal),
+            'L' => Ok(Self::NorthEast),
+            'J' => Ok(Self::NorthWest),
+            '7' => Ok(Self::SouthWest),
+            'F' => Ok(Self::SouthEast),
+            'S' => Ok(Self::Starting),
+            _ => Err("invalid pipe"),
         }
     }
 }
 
 impl Pipe {
-    fn from_char(c: char) -> Option<Self> {
-        match c {
-            '|' => Some(Self::Vertical),
-            '-' => Some(Self::Horizontal),
-            'L' => Some(Self::NorthEast),
-            'J' => Some(Self::NorthWest),
-            '7' => Some(Self::SouthWest),
-            'F' => Some(Self::SouthEast),
-            'S' => Some(Self::Starting),
-            '.' => None,
-            _ => panic!("invalid pipe char: {}", c),
-        }
-    }
+    fn connects(&self, direction: Direction4) -> bool {
+        use Direction4::*;
 
-    fn connections(&self) -> (bool, bool, bool, bool) {
         match self {
-            Self::Vertical => (true, false, true, false),
-            Self::Horizontal => (false, true, false, true),
-            Self::NorthEast => (true, true, false, false),
-            Self::NorthWest => (true, false, false, true),
-            Self::SouthWest => (false, false, true, true),
-            Self::SouthEast => (false, true, true, false),
-            Self::Starting => (true, true, true, true),
+            Self::Vertical => matches!(direction, Up | Down),
+            Self::Horizontal => matches!(direction, Left | Right),
+            Self::NorthEast => matches!(direction, Up | Right),
+            Self::NorthWest => matches!(direction, Up | Left),
+            Self::SouthWest => matches!(direction, Down | Left),
+            Self::SouthEast => matches!(direction, Down | Right),
+            Self::Starting => true,
         }
     }
 
-    fn is_connected_to(&self, next: &Self, port: &Port) -> bool {
-        let (north, east, south, west) = self.connections();
-        let (next_north, next_east, next_south, next_west) = next.connections();
-
-        match port {
-            Port::North => north && next_south,
-            Port::East => east && next_west,
-            Port::South => south && next_north,
-            Port::West => west && next_east,
-        }
+    fn is_connected_to(&self, next: &Self, direction: Direction4) -> bool {
+        self.connects(direction) && next.connects(direction.reverse())
     }
 }
 
-struct Input {
+/// The pipe in every tile and where the animal starts.
+pub struct Input {
     grid: Grid<Option<Pipe>>,
-    starting_point: (usize, usize),
+    starting_point: Point,
 }
 
-fn parse_input(input: &str) -> Input {
-    let mut starting_point = None;
-    let mut grid = Vec::new();
-
-    let mut no_lines = 0;
-
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            if c == 'S' {
-                starting_point = Some((y, x));
-            }
-
-            grid.push(Pipe::from_char(c));
-        }
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let parsed = parse_grid_with(input, input, &['S'], |c| match c {
+        '.' => Ok(None),
+        c => Pipe::try_from(c).map(Some),
+    })?;
 
-        no_lines += 1;
-    }
-    
-    let cols = grid.len() / no_lines;
-    let grid = Grid::from_vec(grid, cols);
+    let starting_point = parsed
+        .marker('S')
+        .ok_or_else(|| ParseError::end_of_input(input, "no starting point found"))?;
 
-    Input {
-        grid,
-        starting_point: starting_point.expect("No starting point found"),
-    }
+    Ok(Input {
+        grid: parsed.grid,
+        starting_point: starting_point.into(),
+    })
 }
 
-fn flood_loop(input: &Input) -> Grid<Option<u16>> {
+fn flood_loop(input: &Input) -> Grid<Option<usize>> {
     let Input {
         grid,
         starting_point,
     } = input;
 
-    let mut frontier = VecDeque::new();
-    frontier.push_back((*starting_point, 0, *starting_point));
-
-    let mut distances = Grid::init(grid.rows(), grid.cols(), None);
-
-    while let Some((pipe, distance, origin)) = frontier.pop_front() {
-        if distances[pipe].is_some() {
-            break;
-        }
-
-        distances[pipe] = Some(distance);
+    let connected = |&pipe: &Point| {
         let current_pipe_shape = grid[pipe].as_ref().unwrap();
 
-        for port in [Port::North, Port::East, Port::South, Port::West].iter() {
-            let (dy, dx) = port.offset();
+        pipe.neighbours(grid).filter_map(move |(direction, next)| {
+            let next_pipe_shape = grid[next].as_ref()?;
+            current_pipe_shape
+                .is_connected_to(next_pipe_shape, direction)
+                .then_some(next)
+        })
+    };
 
-            let next_y = pipe.0.saturating_add_signed(dy);
-            let next_x = pipe.1.saturating_add_signed(dx);
+    let mut seen = Grid::new(grid.rows(), grid.cols());
+    let visit = |&pipe: &Point| !std::mem::replace(&mut seen[pipe], true);
 
-            if (next_y, next_x) == origin {
-                continue;
-            }
-
-            let cell = match grid.get(next_y, next_x) {
-                Some(cell) => cell,
-                None => continue,
-            };
+    let mut distances = Grid::init(grid.rows(), grid.cols(), None);
 
-            if let Some(next_pipe_shape) = cell {
-                if current_pipe_shape.is_connected_to(next_pipe_shape, port) {
-                    frontier.push_back(((next_y, next_x), distance + 1, pipe));
-                }
-            }
-        }
+    for (pipe, distance) in bfs_reach_with(*starting_point, connected, visit) {
+        distances[pipe] = Some(distance);
     }
 
     distances
 }
 
-pub fn part_one(input: &str) -> u16 {
-    let inputs = parse_input(input);
-    let distances = flood_loop(&inputs);
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    let distances = flood_loop(input);
 
-    distances.iter().max().unwrap().unwrap()
+    Ok(distances.iter().max().unwrap().unwrap().into())
 }
 
-pub fn part_two(input: &str) -> u32 {
-    let input = parse_input(input);
-    let distances = flood_loop(&input);
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    let distances = flood_loop(input);
 
-    let mut inside = 0;
+    let mut inside = 0u32;
 
     for y in 0..input.grid.rows() {
         let mut is_inside = false;
@@ -168,7 +122,7 @@ pub fn part_two(input: &str) -> u32 {
             let shape = input.grid[(y, x)].as_ref();
 
             if distances[(y, x)].is_some() {
-                if shape.as_ref().unwrap().connections().0 {
+                if shape.as_ref().unwrap().connects(Direction4::Up) {
                     is_inside = !is_inside;
                 }
 
@@ -181,30 +135,30 @@ pub fn part_two(input: &str) -> u32 {
         }
     }
 
-    inside
+    Ok(inside.into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{read_file, read_file_with_name};
+    use crate::{read_example, read_file};
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 10);
-        assert_eq!(part_one(&input), 8);
+        let input = parse(&read_example(10, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 8);
 
-        let input = read_file("inputs", 10);
-        assert_eq!(part_one(&input), 6599);
+        let input = parse(&read_file("inputs", 10)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 6599);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file_with_name("examples", "10_2");
-        assert_eq!(part_two(&input), 10);
+        let input = parse(&read_example(10, "example2")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 10);
 
-        let input = read_file("inputs", 10);
-        assert_eq!(part_two(&input), 477);
+        let input = parse(&read_file("inputs", 10)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 477);
     }
 }
@@ -1,17 +1,19 @@
 use grid::Grid;
 
-fn parse_input(input: &str) -> Grid<bool> {
-    let mut data = Vec::new();
-    let mut lines = 0;
+use crate::grid::parse_grid_with;
+use crate::{Answer, ParseError, SolveError};
 
-    for line in input.lines() {
-        for c in line.chars() {
-            data.push('#' == c);
-        }
-        lines += 1;
-    }
-    let cols = data.len() / lines;
-    Grid::from_vec(data, cols)
+/// The image, `true` where there is a galaxy.
+pub type Input = Grid<bool>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let parsed = parse_grid_with(input, input, &[], |c| match c {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _ => Err("invalid cell"),
+    })?;
+
+    Ok(parsed.grid)
 }
 
 fn expand(grid: &Grid<bool>, factor: u32) -> Vec<(usize, usize)> {
@@ -70,28 +72,25 @@ fn shortest_pairs(stars: &[(usize, usize)]) -> usize {
     total
 }
 
-pub fn part_one(input: &str) -> usize {
-    let grid = parse_input(input);
-    let mapped_stars = expand(&grid, 2);
-    shortest_pairs(&mapped_stars)
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    let mapped_stars = expand(input, 2);
+    Ok(shortest_pairs(&mapped_stars).into())
 }
 
-pub fn part_two(input: &str) -> usize {
-    let grid = parse_input(input);
-    let mapped_stars = expand(&grid, 1000000);
-    shortest_pairs(&mapped_stars)
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    let mapped_stars = expand(input, 1000000);
+    Ok(shortest_pairs(&mapped_stars).into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::read_example;
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 11);
-        let grid = parse_input(&input);
+        let grid = parse(&read_example(11, "example")).unwrap();
 
         let mapped_stars = expand(&grid, 2);
         assert_eq!(shortest_pairs(&mapped_stars), 374);
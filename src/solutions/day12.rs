@@ -1,5 +1,8 @@
 use rustc_hash::FxHashMap as HashMap;
 
+use crate::parse::{char_at, number, split_once};
+use crate::{Answer, ParseError, SolveError};
+
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 enum SpringState {
     Operational,
@@ -8,30 +11,45 @@ enum SpringState {
 }
 
 impl SpringState {
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            '#' => Self::Broken,
-            '.' => Self::Operational,
-            '?' => Self::Unknown,
-            _ => panic!("invalid spring char: {}", c),
+            '#' => Some(Self::Broken),
+            '.' => Some(Self::Operational),
+            '?' => Some(Self::Unknown),
+            _ => None,
         }
     }
 }
 
 #[derive(Debug, Hash)]
-struct SpringRow {
+pub struct SpringRow {
     row: Vec<SpringState>,
     broken_groups: Vec<usize>,
 }
 
-fn parse_input(input: &str) -> impl Iterator<Item = SpringRow> + '_ {
-    input.lines().map(|line| {
-        let (states, groups) = line.split_once(' ').expect("cant parse line");
-        let row = states.chars().map(SpringState::from_char).collect();
-        let broken_groups = groups.split(',').map(|g| g.parse().unwrap()).collect();
-
-        SpringRow { row, broken_groups }
-    })
+/// The condition records, one per row of springs.
+pub type Input = Vec<SpringRow>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            let (states, groups) = split_once(input, line, " ")?;
+            let row = states
+                .char_indices()
+                .map(|(idx, c)| {
+                    SpringState::from_char(c)
+                        .ok_or_else(|| ParseError::at(input, char_at(states, idx), "invalid spring"))
+                })
+                .collect::<Result<_, _>>()?;
+            let broken_groups = groups
+                .split(',')
+                .map(|g| number(input, g))
+                .collect::<Result<_, _>>()?;
+
+            Ok(SpringRow { row, broken_groups })
+        })
+        .collect()
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -122,16 +140,16 @@ fn check_combinations(row: &[SpringState], groups: &[usize]) -> usize {
     valid
 }
 
-pub fn part_one(input: &str) -> usize {
-    parse_input(input)
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    Ok(input
+        .iter()
         .map(|row| check_combinations(&row.row, &row.broken_groups))
-        .sum()
+        .sum::<usize>()
+        .into())
 }
 
-pub fn part_two(input: &str) -> usize {
-    let inputs = parse_input(input).collect::<Vec<_>>();
-
-    inputs.into_iter().map(|row| {
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    Ok(input.iter().map(|row| {
         let mut new_row = Vec::new();
         let mut new_groups = Vec::new();
 
@@ -145,42 +163,24 @@ pub fn part_two(input: &str) -> usize {
         };
 
         check_combinations(&new_row, &new_groups)
-    }).sum()
+    }).sum::<usize>().into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::read_example;
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = "???.### 1,1,3";
-        assert_eq!(part_one(input), 1);
-
-        let input = ".??..??...?##. 1,1,3";
-        assert_eq!(part_one(input), 4);
-
-        let input = "?#?#?#?#?#?#?#? 1,3,1,6";
-        assert_eq!(part_one(input), 1);
-
-        let input = "????.#...#... 4,1,1";
-        assert_eq!(part_one(input), 1);
-
-        let input = "????.######..#####. 1,6,5";
-        assert_eq!(part_one(input), 4);
-
-        let input = "?###???????? 3,2,1";
-        assert_eq!(part_one(input), 10);
-
-        let input = read_file("examples", 12);
-        assert_eq!(part_one(&input), 21);
+        let input = parse(&read_example(12, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 21);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 12);
-        assert_eq!(part_two(&input), 525152);
+        let input = parse(&read_example(12, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 525152);
     }
 }
@@ -1,7 +1,10 @@
 use grid::Grid;
 
+use crate::grid::parse_grid_with;
+use crate::{Answer, ParseError, SolveError};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Symbol {
+pub enum Symbol {
     Ash,
     Rocks,
 }
@@ -9,30 +12,26 @@ enum Symbol {
 const VERTICAL_MULTIPLIER: usize = 1;
 const HORIZONTAL_MULTIPLIER: usize = 100;
 
-impl Symbol {
-    fn from_char(c: char) -> Self {
+impl TryFrom<char> for Symbol {
+    type Error = &'static str;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            '.' => Self::Ash,
-            '#' => Self::Rocks,
-            _ => panic!("invalid symbol: {}", c),
+            '.' => Ok(Self::Ash),
+            '#' => Ok(Self::Rocks),
+            _ => Err("invalid symbol"),
         }
     }
 }
 
-fn parse_input(input: &str) -> impl Iterator<Item = Grid<Symbol>> + '_ {
-    input.split("\n\n").map(|pattern| {
-        let mut grid_data = Vec::new();
-        let mut width = 0;
-
-        for line in pattern.lines() {
-            width = line.len();
+/// The patterns of ash and rocks.
+pub type Input = Vec<Grid<Symbol>>;
 
-            for c in line.chars() {
-                grid_data.push(Symbol::from_char(c));
-            }
-        }
-        Grid::from_vec(grid_data, width)
-    })
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    input
+        .split("\n\n")
+        .map(|pattern| Ok(parse_grid_with(input, pattern, &[], Symbol::try_from)?.grid))
+        .collect()
 }
 
 fn check_mirror(
@@ -79,19 +78,19 @@ fn check_mirror(
     allow_smudge == has_smudge
 }
 
-fn process_grid(input: &str, allow_smudge: bool) -> usize {
+fn process_grid(input: &Input, allow_smudge: bool) -> usize {
     let mut total = 0;
 
-    'pattern: for grid in parse_input(input) {
+    'pattern: for grid in input {
         for x in 0..(grid.cols() - 1) {
-            if check_mirror(&grid, x, true, allow_smudge) {
+            if check_mirror(grid, x, true, allow_smudge) {
                 total += (x + 1) * VERTICAL_MULTIPLIER;
                 continue 'pattern;
             }
         }
 
         for y in 0..(grid.rows() - 1) {
-            if check_mirror(&grid, y, false, allow_smudge) {
+            if check_mirror(grid, y, false, allow_smudge) {
                 total += (y + 1) * HORIZONTAL_MULTIPLIER;
                 continue 'pattern;
             }
@@ -101,29 +100,29 @@ fn process_grid(input: &str, allow_smudge: bool) -> usize {
     total
 }
 
-pub fn part_one(input: &str) -> usize {
-    process_grid(input, false)
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    Ok(process_grid(input, false).into())
 }
 
-pub fn part_two(input: &str) -> usize {
-    process_grid(input, true)
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    Ok(process_grid(input, true).into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::read_example;
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 13);
-        assert_eq!(part_one(&input), 405);
+        let input = parse(&read_example(13, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 405);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 13);
-        assert_eq!(part_two(&input), 400);
+        let input = parse(&read_example(13, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 400);
     }
 }
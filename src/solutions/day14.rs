@@ -1,55 +1,44 @@
 use grid::Grid;
 use rustc_hash::FxHashMap as HashMap;
 
+use crate::geom::Direction4;
+use crate::grid::parse_grid;
+use crate::{Answer, ParseError, SolveError};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
-enum Symbol {
+pub enum Symbol {
     #[default]
     Empty,
     Round,
     Cube,
 }
 
-impl Symbol {
-    fn from_char(c: char) -> Self {
+impl TryFrom<char> for Symbol {
+    type Error = &'static str;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            'O' => Self::Round,
-            '#' => Self::Cube,
-            '.' => Self::Empty,
-            _ => panic!("invalid symbol: {}", c),
+            'O' => Ok(Self::Round),
+            '#' => Ok(Self::Cube),
+            '.' => Ok(Self::Empty),
+            _ => Err("invalid symbol"),
         }
     }
 }
 
-fn parse_input(input: &str) -> Grid<Symbol> {
-    let mut cols: Option<usize> = None;
-
-    let grid_data: Vec<Symbol> = input
-        .lines()
-        .enumerate()
-        .flat_map(|(index, line)| {
-            if index == 0 {
-                cols = Some(line.len());
-            }
-            line.chars().map(Symbol::from_char)
-        })
-        .collect();
-
-    Grid::from_vec(grid_data, cols.expect("no data"))
-}
+/// The platform with its rocks.
+pub type Input = Grid<Symbol>;
 
-enum Direction {
-    North,
-    East,
-    South,
-    West,
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    Ok(parse_grid(input, &[])?.grid)
 }
 
-fn tilt_platform(grid: &mut Grid<Symbol>, direction: Direction) {
+fn tilt_platform(grid: &mut Grid<Symbol>, direction: Direction4) {
     match direction {
-        Direction::North => grid.rotate_right(),
-        Direction::East => {}
-        Direction::South => grid.rotate_left(),
-        Direction::West => grid.rotate_half(),
+        Direction4::Up => grid.rotate_right(),
+        Direction4::Right => {}
+        Direction4::Down => grid.rotate_left(),
+        Direction4::Left => grid.rotate_half(),
     };
 
     let mut new_grid_data = Vec::with_capacity(grid.rows() * grid.cols());
@@ -90,10 +79,10 @@ fn tilt_platform(grid: &mut Grid<Symbol>, direction: Direction) {
     let mut new_grid = Grid::from_vec(new_grid_data, grid.cols());
 
     match direction {
-        Direction::North => new_grid.rotate_left(),
-        Direction::East => {}
-        Direction::South => new_grid.rotate_right(),
-        Direction::West => new_grid.rotate_half(),
+        Direction4::Up => new_grid.rotate_left(),
+        Direction4::Right => {}
+        Direction4::Down => new_grid.rotate_right(),
+        Direction4::Left => new_grid.rotate_half(),
     };
 
     *grid = new_grid;
@@ -111,22 +100,22 @@ fn get_weight(grid: &Grid<Symbol>) -> usize {
         .sum()
 }
 
-pub fn part_one(input: &str) -> usize {
-    let mut grid = parse_input(input);
-    tilt_platform(&mut grid, Direction::North);
-    get_weight(&grid)
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    let mut grid = input.clone();
+    tilt_platform(&mut grid, Direction4::Up);
+    Ok(get_weight(&grid).into())
 }
 
-pub fn part_two(input: &str) -> usize {
-    let mut grid = parse_input(input);
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    let mut grid = input.clone();
     let mut seen = HashMap::default();
     let mut i = 0;
 
     let tilt_cycle = |grid: &mut Grid<_>| {
-        tilt_platform(grid, Direction::North);
-        tilt_platform(grid, Direction::West);
-        tilt_platform(grid, Direction::South);
-        tilt_platform(grid, Direction::East);
+        tilt_platform(grid, Direction4::Up);
+        tilt_platform(grid, Direction4::Left);
+        tilt_platform(grid, Direction4::Down);
+        tilt_platform(grid, Direction4::Right);
     };
 
     loop {
@@ -148,27 +137,27 @@ pub fn part_two(input: &str) -> usize {
         }
     }
 
-    get_weight(&grid)
+    Ok(get_weight(&grid).into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::{read_example, read_file};
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 14);
-        assert_eq!(part_one(&input), 136);
+        let input = parse(&read_example(14, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 136);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 14);
-        assert_eq!(part_two(&input), 64);
+        let input = parse(&read_example(14, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 64);
 
-        let input = read_file("inputs", 14);
-        assert_eq!(part_two(&input), 102509);
+        let input = parse(&read_file("inputs", 14)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 102509);
     }
 }
@@ -1,3 +1,6 @@
+use crate::parse::number;
+use crate::{Answer, ParseError, SolveError};
+
 fn calculate_hash(input: &str) -> u32 {
     let mut v = 0;
 
@@ -15,27 +18,46 @@ enum Operation {
     Remove,
 }
 
-struct Instruction<'a> {
+/// A step of the initialization sequence, `text` is the step as written.
+pub struct Instruction {
+    text: String,
     operation: Operation,
-    label: &'a str,
+    label: String,
 }
 
-fn parse_instruction(input: &str) -> Instruction<'_> {
-    let (label, focal_length) = input.split_once(['=', '-']).expect("invalid instruction");
+fn parse_instruction(input: &str, step: &str) -> Result<Instruction, ParseError> {
+    let (label, focal_length) = step
+        .split_once(['=', '-'])
+        .ok_or_else(|| ParseError::at(input, step, "invalid instruction"))?;
 
     let operation = match focal_length {
         "" => Operation::Remove,
-        len => {
-            let len = len.parse().expect("invalid focal length");
-            Operation::Insert(len)
-        }
+        len => Operation::Insert(number(input, len)?),
     };
 
-    Instruction { operation, label }
+    Ok(Instruction {
+        text: step.to_string(),
+        operation,
+        label: label.to_string(),
+    })
+}
+
+/// The steps of the initialization sequence.
+pub type Input = Vec<Instruction>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    input
+        .split(',')
+        .map(|step| parse_instruction(input, step))
+        .collect()
 }
 
-pub fn part_one(input: &str) -> u32 {
-    input.split(',').map(calculate_hash).sum()
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    Ok(input
+        .iter()
+        .map(|instruction| calculate_hash(&instruction.text))
+        .sum::<u32>()
+        .into())
 }
 
 #[derive(Default, Debug, Clone)]
@@ -64,25 +86,25 @@ impl<'a> HashMap<'a> {
     }
 }
 
-pub fn part_two(input: &str) -> usize {
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
     let mut boxes = vec![HashMap::new(); 256];
 
-    for instruction in input.split(',').map(parse_instruction) {
-        let hash = calculate_hash(instruction.label);
+    for instruction in input {
+        let hash = calculate_hash(&instruction.label);
 
         let lens_box = &mut boxes[hash as usize];
 
         match instruction.operation {
             Operation::Insert(len) => {
-                lens_box.insert(instruction.label, len);
+                lens_box.insert(&instruction.label, len);
             }
             Operation::Remove => {
-                lens_box.remove(instruction.label);
+                lens_box.remove(&instruction.label);
             }
         }
     }
 
-    boxes
+    Ok(boxes
         .into_iter()
         .enumerate()
         .map(|(box_idx, lens_box)| {
@@ -93,24 +115,25 @@ pub fn part_two(input: &str) -> usize {
                 .map(|(lens_idx, (_, focal))| (box_idx + 1) * (lens_idx + 1) * focal as usize)
                 .sum::<usize>()
         })
-        .sum()
+        .sum::<usize>()
+        .into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::read_example;
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 15);
-        assert_eq!(part_one(&input), 1320);
+        let input = parse(&read_example(15, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 1320);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 15);
-        assert_eq!(part_two(&input), 145);
+        let input = parse(&read_example(15, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 145);
     }
 }
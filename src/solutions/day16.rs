@@ -1,11 +1,16 @@
-use std::{cmp, collections::VecDeque};
+use std::cmp;
 
 use bitvec::bitvec;
 use grid::Grid;
 use smallvec::{smallvec, SmallVec};
 
+use crate::geom::{Direction4, Point};
+use crate::grid::parse_grid;
+use crate::search::bfs_reach_with;
+use crate::{Answer, ParseError, SolveError};
+
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
-enum Symbol {
+pub enum Symbol {
     Empty,
     UpwardMirror,
     DownwardMirror,
@@ -13,157 +18,131 @@ enum Symbol {
     HorizontalSplitter,
 }
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+impl TryFrom<char> for Symbol {
+    type Error = &'static str;
 
-impl Symbol {
-    fn from_char(c: char) -> Self {
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            '.' => Self::Empty,
-            '/' => Self::UpwardMirror,
-            '\\' => Self::DownwardMirror,
-            '|' => Self::VerticalSplitter,
-            '-' => Self::HorizontalSplitter,
-            _ => panic!("invalid symbol"),
+            '.' => Ok(Self::Empty),
+            '/' => Ok(Self::UpwardMirror),
+            '\\' => Ok(Self::DownwardMirror),
+            '|' => Ok(Self::VerticalSplitter),
+            '-' => Ok(Self::HorizontalSplitter),
+            _ => Err("invalid symbol"),
         }
     }
+}
 
-    fn next_direction(&self, direction: Direction) -> SmallVec<[Direction; 2]> {
+impl Symbol {
+    fn next_direction(&self, direction: Direction4) -> SmallVec<[Direction4; 2]> {
         match self {
             Self::Empty => smallvec![direction],
             Self::UpwardMirror => match direction {
-                Direction::Up => smallvec![Direction::Right],
-                Direction::Down => smallvec![Direction::Left],
-                Direction::Left => smallvec![Direction::Down],
-                Direction::Right => smallvec![Direction::Up],
+                Direction4::Up => smallvec![Direction4::Right],
+                Direction4::Down => smallvec![Direction4::Left],
+                Direction4::Left => smallvec![Direction4::Down],
+                Direction4::Right => smallvec![Direction4::Up],
             },
             Self::DownwardMirror => match direction {
-                Direction::Up => smallvec![Direction::Left],
-                Direction::Down => smallvec![Direction::Right],
-                Direction::Left => smallvec![Direction::Up],
-                Direction::Right => smallvec![Direction::Down],
+                Direction4::Up => smallvec![Direction4::Left],
+                Direction4::Down => smallvec![Direction4::Right],
+                Direction4::Left => smallvec![Direction4::Up],
+                Direction4::Right => smallvec![Direction4::Down],
             },
             Self::VerticalSplitter => match direction {
-                Direction::Up => smallvec![Direction::Up],
-                Direction::Down => smallvec![Direction::Down],
-                Direction::Left => smallvec![Direction::Up, Direction::Down],
-                Direction::Right => smallvec![Direction::Up, Direction::Down],
+                Direction4::Up => smallvec![Direction4::Up],
+                Direction4::Down => smallvec![Direction4::Down],
+                Direction4::Left => smallvec![Direction4::Up, Direction4::Down],
+                Direction4::Right => smallvec![Direction4::Up, Direction4::Down],
             },
             Self::HorizontalSplitter => match direction {
-                Direction::Up => smallvec![Direction::Left, Direction::Right],
-                Direction::Down => smallvec![Direction::Left, Direction::Right],
-                Direction::Left => smallvec![Direction::Left],
-                Direction::Right => smallvec![Direction::Right],
+                Direction4::Up => smallvec![Direction4::Left, Direction4::Right],
+                Direction4::Down => smallvec![Direction4::Left, Direction4::Right],
+                Direction4::Left => smallvec![Direction4::Left],
+                Direction4::Right => smallvec![Direction4::Right],
             },
         }
     }
 }
 
-fn parse_input(input: &str) -> Grid<Symbol> {
-    let mut cols: Option<usize> = None;
-
-    let grid_data: Vec<Symbol> = input
-        .lines()
-        .enumerate()
-        .flat_map(|(index, line)| {
-            if index == 0 {
-                cols = Some(line.len());
-            }
-            line.chars().map(Symbol::from_char)
-        })
-        .collect();
-
-    Grid::from_vec(grid_data, cols.expect("no data"))
+/// The contraption with its mirrors and splitters.
+pub type Input = Grid<Symbol>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    Ok(parse_grid(input, &[])?.grid)
 }
 
-fn run_from_tile(
-    grid: &Grid<Symbol>,
-    start_direction: Direction,
-    position: (usize, usize),
-) -> usize {
-    let mut frontier = VecDeque::new();
-    frontier.push_back((position, start_direction));
+fn run_from_tile(grid: &Grid<Symbol>, start_direction: Direction4, position: Point) -> usize {
+    let beams = |&(cell, direction): &(Point, Direction4)| {
+        grid[cell]
+            .next_direction(direction)
+            .into_iter()
+            .filter_map(move |next_direction| {
+                Some((cell.step_in(next_direction, grid)?, next_direction))
+            })
+    };
 
     let mut visited = bitvec![0; grid.cols() * grid.rows() * 4];
 
-    let mut visit = |next_cell: (usize, usize), next_direction| {
-        let idx = (next_cell.0 * grid.cols() + next_cell.1) * 4 + next_direction as usize;
+    let visit = |&(cell, direction): &(Point, Direction4)| {
+        let idx = (cell.y * grid.cols() + cell.x) * 4 + direction as usize;
         !visited.replace(idx, true)
     };
 
     let mut energized = Grid::new(grid.rows(), grid.cols());
 
-    while let Some(((y, x), direction)) = frontier.pop_front() {
-        *energized.get_mut(y, x).unwrap() = true;
-        let symbol = grid.get(y, x).expect("invalid cell");
-        let next_directions = symbol.next_direction(direction);
-
-        for next_direction in next_directions {
-            let next_cell = match next_direction {
-                Direction::Up if y > 0 => (y - 1, x),
-                Direction::Down if y < (grid.rows() - 1) => (y + 1, x),
-                Direction::Left if x > 0 => (y, x - 1),
-                Direction::Right if x < (grid.cols() - 1) => (y, x + 1),
-                _ => continue,
-            };
-
-            if visit(next_cell, next_direction) {
-                frontier.push_back((next_cell, next_direction));
-            };
-        }
+    for ((cell, _), _) in bfs_reach_with((position, start_direction), beams, visit) {
+        energized[cell] = true;
     }
 
     energized.iter().filter(|x| **x).count()
 }
 
-pub fn part_one(input: &str) -> usize {
-    let input = parse_input(input);
-    run_from_tile(&input, Direction::Right, (0, 0))
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    Ok(run_from_tile(input, Direction4::Right, Point::new(0, 0)).into())
 }
 
-pub fn part_two(input: &str) -> usize {
-    let grid = parse_input(input);
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    let grid = input;
     let mut max = 0;
 
     for x in 0..grid.cols() {
-        max = cmp::max(max, run_from_tile(&grid, Direction::Down, (0, x)));
+        max = cmp::max(max, run_from_tile(grid, Direction4::Down, Point::new(0, x)));
         max = cmp::max(
             max,
-            run_from_tile(&grid, Direction::Up, (grid.rows() - 1, x)),
+            run_from_tile(grid, Direction4::Up, Point::new(grid.rows() - 1, x)),
         );
     }
 
     for y in 0..grid.rows() {
-        max = cmp::max(max, run_from_tile(&grid, Direction::Right, (y, 0)));
         max = cmp::max(
             max,
-            run_from_tile(&grid, Direction::Left, (y, grid.cols() - 1)),
+            run_from_tile(grid, Direction4::Right, Point::new(y, 0)),
+        );
+        max = cmp::max(
+            max,
+            run_from_tile(grid, Direction4::Left, Point::new(y, grid.cols() - 1)),
         );
     }
 
-    max
+    Ok(max.into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::read_example;
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 16);
-        assert_eq!(part_one(&input), 46);
+        let input = parse(&read_example(16, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 46);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 16);
-        assert_eq!(part_two(&input), 51);
+        let input = parse(&read_example(16, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 51);
     }
 }
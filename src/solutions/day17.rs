@@ -1,214 +1,260 @@
-use std::{
-    cmp::Reverse,
-    collections::BinaryHeap,
-};
-
-use rustc_hash::FxHashMap as HashMap;
-
 use grid::Grid;
 
-fn parse_input(input: &str) -> Grid<u8> {
-    let mut cols: Option<usize> = None;
+use crate::geom::{Direction4, Point};
+use crate::grid::parse_grid_with;
+use crate::search::astar;
+use crate::{Answer, ParseError, SolveError};
 
-    let grid_data: Vec<u8> = input
-        .lines()
-        .enumerate()
-        .flat_map(|(index, line)| {
-            if index == 0 {
-                cols = Some(line.len());
-            }
-            line.chars()
-                .map(|c| c.to_digit(10).expect("invalid digit") as u8)
-        })
-        .collect();
+/// The heat loss of every city block.
+pub type Input = Grid<u8>;
 
-    Grid::from_vec(grid_data, cols.expect("no data"))
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let parsed = parse_grid_with(input, input, &[], |c| {
+        c.to_digit(10).map(|digit| digit as u8).ok_or("invalid digit")
+    })?;
+
+    Ok(parsed.grid)
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+/// How a crucible may move between the lava pool and the factory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrucibleRules {
+    /// The blocks it has to move in a straight line before it can turn or
+    /// stop at the factory.
+    pub min_straight: usize,
+    /// The blocks it can move in a straight line before it has to turn.
+    pub max_straight: usize,
+    /// Whether it can turn around instead of only left or right.
+    pub allow_reverse: bool,
 }
 
-impl Direction {
-    fn delta(&self) -> (isize, isize) {
-        match self {
-            Self::Up => (-1, 0),
-            Self::Down => (1, 0),
-            Self::Left => (0, -1),
-            Self::Right => (0, 1),
-        }
-    }
+impl CrucibleRules {
+    /// The crucible of part one, at most three blocks in a straight line.
+    pub const CRUCIBLE: Self = Self {
+        min_straight: 1,
+        max_straight: 3,
+        allow_reverse: false,
+    };
+
+    /// The ultra crucible of part two, four to ten blocks in a straight line.
+    pub const ULTRA_CRUCIBLE: Self = Self {
+        min_straight: 4,
+        max_straight: 10,
+        allow_reverse: false,
+    };
 
-    fn turn_back(&self) -> Self {
-        match self {
-            Self::Up => Self::Down,
-            Self::Down => Self::Up,
-            Self::Left => Self::Right,
-            Self::Right => Self::Left,
+    /// Whether the crucible can set off in `next` after moving in `prev`, or
+    /// from the start if there is no `prev`.
+    fn can_turn(&self, prev: Option<Direction4>, next: Direction4) -> bool {
+        match prev {
+            None => true,
+            Some(prev) if prev == next => false,
+            Some(prev) => self.allow_reverse || next != prev.reverse(),
         }
     }
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+/// Where the crucible stopped after a straight run, and the direction it
+/// moved in. The search moves a whole run at a time, so every state already
+/// follows the straight line rules.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 struct State {
-    f_cost: usize,
-    g_cost: usize,
-    prev_direction: Direction,
-    moves_since_last_turn: usize,
-    position: (usize, usize),
+    position: Point,
+    direction: Option<Direction4>,
 }
 
-fn find_min_path(
-    grid: &Grid<u8>,
-    is_valid_move: impl Fn(Direction, Direction, usize) -> bool,
-) -> usize {
-    let mut dist = HashMap::default();
-    let mut heap = BinaryHeap::new();
+/// The way the crucible takes from the lava pool to the factory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub heat_loss: usize,
+    /// Every block the crucible moves into, with the direction it moved in.
+    pub steps: Vec<(Point, Direction4)>,
+}
 
-    let goal = (grid.rows() - 1, grid.cols() - 1);
+impl Route {
+    /// Draws the route onto the map like the puzzle does, with an arrow on
+    /// every block the crucible moves into.
+    pub fn render(&self, grid: &Grid<u8>) -> String {
+        let mut cells: Grid<char> = Grid::init(grid.rows(), grid.cols(), ' ');
 
-    let manhattan_distance = |pos: (usize, usize)| (goal.0 - pos.0 + goal.1 - pos.1);
+        for ((y, x), &heat_loss) in grid.indexed_iter() {
+            cells[(y, x)] = char::from(b'0' + heat_loss);
+        }
 
-    let state_key = |state: &State| {
-        (
-            state.position,
-            state.prev_direction,
-            state.moves_since_last_turn,
-        )
-    };
+        for &(position, direction) in &self.steps {
+            cells[position] = direction.arrow();
+        }
+
+        cells
+            .iter_rows()
+            .map(|row| row.collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
 
-    let start_state = State {
-        f_cost: manhattan_distance((0, 0)),
-        g_cost: 0,
-        prev_direction: Direction::Right,
-        moves_since_last_turn: 0,
-        position: (0, 0),
+/// The route with the least heat loss from the top left to the bottom right
+/// block for a crucible following `rules`.
+pub fn find_route(grid: &Grid<u8>, rules: CrucibleRules) -> Option<Route> {
+    let start = State {
+        position: Point::new(0, 0),
+        direction: None,
     };
 
-    dist.insert(state_key(&start_state), 0);
-    heap.push(Reverse(start_state));
+    let goal = Point::new(grid.rows() - 1, grid.cols() - 1);
 
-    while let Some(Reverse(state)) = heap.pop() {
-        if state.position == goal {
-            return dist[&state_key(&state)];
-        }
+    let successors = |state: &State| {
+        let mut next = Vec::new();
 
-        for &next_dir in &[
-            Direction::Up,
-            Direction::Down,
-            Direction::Left,
-            Direction::Right,
-        ] {
-            if !is_valid_move(state.prev_direction, next_dir, state.moves_since_last_turn) {
+        for direction in Direction4::ALL {
+            if !rules.can_turn(state.direction, direction) {
                 continue;
             }
 
-            let (dy, dx) = next_dir.delta();
+            let mut position = state.position;
+            let mut heat_loss = 0;
 
-            if (dy == -1 && state.position.0 == 0)
-                || (dx == -1 && state.position.1 == 0)
-                || (dy == 1 && state.position.0 == grid.rows() - 1)
-                || (dx == 1 && state.position.1 == grid.cols() - 1)
-            {
-                continue;
-            }
+            for blocks in 1..=rules.max_straight {
+                let Some(next_position) = position.step_in(direction, grid) else {
+                    break;
+                };
+
+                position = next_position;
+                heat_loss += grid[position] as usize;
 
-            let next_position = (
-                state.position.0.saturating_add_signed(dy),
-                state.position.1.saturating_add_signed(dx),
-            );
-
-            let moves_count = if next_dir == state.prev_direction {
-                state.moves_since_last_turn + 1
-            } else {
-                1
-            };
-
-            let new_g_cost = state.g_cost + grid[next_position] as usize;
-            let new_f_cost = new_g_cost + manhattan_distance(next_position);
-
-            let next_state = State {
-                f_cost: new_f_cost,
-                g_cost: new_g_cost,
-                prev_direction: next_dir,
-                moves_since_last_turn: moves_count,
-                position: next_position,
-            };
-
-            let key = state_key(&next_state);
-            let current_g_cost = dist.entry(key).or_insert(usize::MAX);
-
-            if new_g_cost < *current_g_cost {
-                heap.push(Reverse(next_state));
-                *current_g_cost = new_g_cost;
+                if blocks >= rules.min_straight {
+                    let direction = Some(direction);
+                    next.push((State { position, direction }, heat_loss));
+                }
             }
         }
-    }
 
-    panic!("no path found");
-}
+        next
+    };
 
-pub fn part_one(input: &str) -> usize {
-    let grid = parse_input(input);
+    // Every block on the way to the goal loses at least the cheapest block of
+    // the map, so the estimate never exceeds the real heat loss, even on maps
+    // with blocks that lose nothing.
+    let min_loss = grid.iter().copied().min().unwrap_or(0) as usize;
 
-    find_min_path(
-        &grid,
-        |prev_direction, next_direction, moves_since_last_turn| {
-            if next_direction == prev_direction.turn_back() {
-                return false;
-            }
+    let found = astar(
+        start,
+        successors,
+        |state| state.position.manhattan(goal) * min_loss,
+        |state| state.position == goal,
+    )?;
 
-            if moves_since_last_turn == 3 && next_direction == prev_direction {
-                return false;
-            }
+    let mut steps = Vec::new();
 
-            true
-        },
-    )
-}
+    for run in found.path().windows(2) {
+        let (mut position, end) = (run[0].position, run[1].position);
+        let direction = run[1].direction.unwrap();
 
-pub fn part_two(input: &str) -> usize {
-    let grid = parse_input(input);
+        while position != end {
+            position = position.step(direction).unwrap();
+            steps.push((position, direction));
+        }
+    }
 
-    find_min_path(
-        &grid,
-        |prev_direction, next_direction, moves_since_last_turn| {
-            if next_direction == prev_direction.turn_back() {
-                return false;
-            }
+    Some(Route {
+        heat_loss: found.cost,
+        steps,
+    })
+}
 
-            if moves_since_last_turn == 10 && next_direction == prev_direction {
-                return false;
-            }
+fn min_heat_loss(input: &Input, rules: CrucibleRules) -> Result<Answer, SolveError> {
+    find_route(input, rules)
+        .map(|route| Answer::from(route.heat_loss))
+        .ok_or_else(|| SolveError("no path to the factory".to_string()))
+}
 
-            if moves_since_last_turn < 4 && next_direction != prev_direction {
-                return false;
-            }
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    min_heat_loss(input, CrucibleRules::CRUCIBLE)
+}
 
-            true
-        },
-    )
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    min_heat_loss(input, CrucibleRules::ULTRA_CRUCIBLE)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::read_example;
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 17);
-        assert_eq!(part_one(&input), 102);
+        let input = parse(&read_example(17, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 102);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 17);
-        assert_eq!(part_two(&input), 94);
+        let input = parse(&read_example(17, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 94);
+
+        let input = parse(&read_example(17, "example2")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 71);
+    }
+
+    #[test]
+    fn test_free_blocks() {
+        // The plain manhattan distance overestimates on `0` blocks and would
+        // settle for a heat loss of 6.
+        let input = parse("668\n302\n000\n061\n002").unwrap();
+        assert_eq!(part_one(&input).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_rules() {
+        let input = parse("19999\n11111\n99991").unwrap();
+        let no_turns = CrucibleRules {
+            min_straight: 5,
+            max_straight: 5,
+            allow_reverse: false,
+        };
+        assert_eq!(find_route(&input, no_turns), None);
+
+        // The ultra crucible has to go down first and cannot stop after three
+        // blocks.
+        let input = parse("1\n1\n1\n1\n1").unwrap();
+        assert_eq!(find_route(&input, CrucibleRules::ULTRA_CRUCIBLE).unwrap().heat_loss, 4);
+        let input = parse("1\n1\n1\n1").unwrap();
+        assert_eq!(find_route(&input, CrucibleRules::ULTRA_CRUCIBLE), None);
+
+        let input = parse("1111").unwrap();
+        let mut rules = CrucibleRules {
+            min_straight: 1,
+            max_straight: 2,
+            allow_reverse: false,
+        };
+        assert_eq!(find_route(&input, rules), None);
+
+        rules.allow_reverse = true;
+        assert_eq!(find_route(&input, rules).unwrap().heat_loss, 5);
+    }
+
+    #[test]
+    fn test_route() {
+        let input = parse(&read_example(17, "example")).unwrap();
+
+        for rules in [CrucibleRules::CRUCIBLE, CrucibleRules::ULTRA_CRUCIBLE] {
+            let route = find_route(&input, rules).unwrap();
+            let heat_loss: usize = route.steps.iter().map(|&(p, _)| input[p] as usize).sum();
+            assert_eq!(heat_loss, route.heat_loss);
+
+            let mut previous = Point::new(0, 0);
+            for &(position, direction) in &route.steps {
+                assert_eq!(previous.step(direction), Some(position));
+                previous = position;
+            }
+
+            let straight: Vec<_> = route.steps.chunk_by(|a, b| a.1 == b.1).map(|run| run.len()).collect();
+            assert!(straight.iter().all(|&run| (rules.min_straight..=rules.max_straight).contains(&run)));
+        }
+
+        let input = parse("12\n34").unwrap();
+        let route = find_route(&input, CrucibleRules::CRUCIBLE).unwrap();
+        assert_eq!(route.render(&input), "1>\n3v");
     }
 }
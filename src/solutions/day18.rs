@@ -3,50 +3,66 @@ use std::collections::HashSet;
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+use crate::geom::Direction4;
+use crate::parse::number;
+use crate::{Answer, ParseError, SolveError};
+
 static INPUT_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"([UDLR]) (\d+) \(#([0-9a-f]+)\)").unwrap());
-
-#[derive(Debug)]
-enum Direction {
-    Right,
-    Down,
-    Left,
-    Up,
+    Lazy::new(|| Regex::new(r"^([UDLR]) (\d+) \(#([0-9a-f]{6})\)$").unwrap());
+
+fn direction_from_char(s: &str) -> Option<Direction4> {
+    match s {
+        "U" => Some(Direction4::Up),
+        "D" => Some(Direction4::Down),
+        "L" => Some(Direction4::Left),
+        "R" => Some(Direction4::Right),
+        _ => None,
+    }
 }
 
-impl Direction {
-    fn from_char(s: &str) -> Self {
-        match s {
-            "U" => Self::Up,
-            "D" => Self::Down,
-            "L" => Self::Left,
-            "R" => Self::Right,
-            _ => panic!("invalid direction"),
-        }
-    }
-
-    fn from_idx(s: &str) -> Self {
-        match s {
-            "0" => Self::Right,
-            "1" => Self::Down,
-            "2" => Self::Left,
-            "3" => Self::Up,
-            _ => panic!("invalid direction"),
-        }
+fn direction_from_idx(s: &str) -> Option<Direction4> {
+    match s {
+        "0" => Some(Direction4::Right),
+        "1" => Some(Direction4::Down),
+        "2" => Some(Direction4::Left),
+        "3" => Some(Direction4::Up),
+        _ => None,
     }
 }
 
-fn parse_input(input: &str) -> impl Iterator<Item = (Direction, u16, &str)> {
-    INPUT_REGEX.captures_iter(input).map(|m| {
-        let direction = Direction::from_char(m.get(1).unwrap().as_str());
-        let distance = m.get(2).unwrap().as_str().parse::<u16>().unwrap();
-        let rgb_code = m.get(3).unwrap().as_str();
+/// The dig plan, read once from the direction and distance columns and once
+/// from the colour codes.
+pub struct Input {
+    dig_plan: Vec<(Direction4, u16)>,
+    hex_plan: Vec<(Direction4, u32)>,
+}
 
-        (direction, distance, rgb_code)
-    })
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let (dig_plan, hex_plan) = input
+        .lines()
+        .map(|line| {
+            let m = INPUT_REGEX
+                .captures(line)
+                .ok_or_else(|| ParseError::at(input, line, "invalid dig instruction"))?;
+
+            let direction = m.get(1).unwrap().as_str();
+            let direction = direction_from_char(direction)
+                .ok_or_else(|| ParseError::at(input, direction, "invalid direction"))?;
+            let distance = number(input, m.get(2).unwrap().as_str())?;
+            let hex = m.get(3).unwrap().as_str();
+            let hex_distance = u32::from_str_radix(&hex[..5], 16)
+                .map_err(|_| ParseError::at(input, &hex[..5], "invalid distance"))?;
+            let hex_direction = direction_from_idx(&hex[5..])
+                .ok_or_else(|| ParseError::at(input, &hex[5..], "invalid direction"))?;
+
+            Ok(((direction, distance), (hex_direction, hex_distance)))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Input { dig_plan, hex_plan })
 }
 
-pub fn part_one(input: &str) -> u32 {
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
     let mut position = (0, 0);
     let mut cells = HashSet::new();
 
@@ -55,13 +71,8 @@ pub fn part_one(input: &str) -> u32 {
     let (mut min_x, mut max_x) = (0, 0);
     let (mut min_y, mut max_y) = (0, 0);
 
-    for (direction, distance, _) in parse_input(input) {
-        let (dy, dx) = match direction {
-            Direction::Up => (-1, 0),
-            Direction::Down => (1, 0),
-            Direction::Left => (0, -1),
-            Direction::Right => (0, 1),
-        };
+    for &(direction, distance) in &input.dig_plan {
+        let (dy, dx) = direction.delta();
 
         for _ in 0..distance {
             position.0 += dy;
@@ -76,7 +87,7 @@ pub fn part_one(input: &str) -> u32 {
         }
     }
 
-    let mut area = 0;
+    let mut area = 0u32;
 
     for y in min_y..=max_y {
         let mut outside = true;
@@ -93,24 +104,17 @@ pub fn part_one(input: &str) -> u32 {
         }
     }
 
-    area
+    Ok(area.into())
 }
 
-pub fn part_two(input: &str) -> i64 {
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
     let mut position = (0, 0);
     let mut edge_distance: i64 = 0;
     let mut area = 0;
 
-    for (_, _, hex) in parse_input(input) {
-        let distance = u32::from_str_radix(&hex[..5], 16).unwrap();
-        let direction = Direction::from_idx(&hex[5..]);
-
-        let (dy, dx) = match direction {
-            Direction::Up => (-1, 0),
-            Direction::Down => (1, 0),
-            Direction::Left => (0, -1),
-            Direction::Right => (0, 1),
-        };
+    for &(direction, distance) in &input.hex_plan {
+        let (dy, dx) = direction.delta();
+        let (dy, dx) = (dy as i64, dx as i64);
 
         let next_position = (
             position.0 + dy * distance as i64,
@@ -124,24 +128,24 @@ pub fn part_two(input: &str) -> i64 {
     }
 
     let inner = (area / 2) - (edge_distance / 2) + 1;
-    inner + edge_distance
+    Ok((inner + edge_distance).into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::read_example;
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 18);
-        assert_eq!(part_one(&input), 62);
+        let input = parse(&read_example(18, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 62);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 18);
-        assert_eq!(part_two(&input), 952408144115);
+        let input = parse(&read_example(18, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 952408144115u64);
     }
 }
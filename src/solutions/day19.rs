@@ -4,9 +4,12 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 use smallvec::{smallvec, SmallVec};
 
-static WORKFLOW_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\w+)\{(.+)\}").unwrap());
+use crate::parse::{char_at, number, split_once};
+use crate::{Answer, ParseError, SolveError};
+
+static WORKFLOW_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\w+)\{(.+)\}$").unwrap());
 static INPUT_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"\{x=(\d+),m=(\d+),a=(\d+),s=(\d+)\}").unwrap());
+    Lazy::new(|| Regex::new(r"^\{x=(\d+),m=(\d+),a=(\d+),s=(\d+)\}$").unwrap());
 
 #[derive(Debug)]
 struct Step {
@@ -15,27 +18,46 @@ struct Step {
 }
 
 impl Step {
-    fn from_str(s: &str) -> Self {
+    fn parse(input: &str, s: &str) -> Result<Self, ParseError> {
         match s.split_once(':') {
             Some((condition, action)) => {
-                let mut chars = condition.chars();
-                let variable = Variable::from_char(chars.next().unwrap());
-                let relation = Relation::from_char(chars.next().unwrap());
-                let value = chars.as_str().parse::<u16>().unwrap();
+                let mut chars = condition.char_indices();
+
+                let variable = chars
+                    .next()
+                    .and_then(|(_, c)| Variable::from_char(c))
+                    .ok_or_else(|| {
+                        ParseError::at(input, char_at(condition, 0), "invalid variable")
+                    })?;
+                let relation = chars
+                    .next()
+                    .and_then(|(_, c)| Relation::from_char(c))
+                    .ok_or_else(|| {
+                        ParseError::at(input, char_at(condition, 1), "invalid relation")
+                    })?;
+                let value = number(input, chars.as_str())?;
+
+                if matches!(relation, Relation::Less) && value == 0 {
+                    return Err(ParseError::at(
+                        input,
+                        chars.as_str(),
+                        "ratings cannot be below 0",
+                    ));
+                }
 
-                Step {
+                Ok(Step {
                     condition: Some(Condition {
                         variable,
                         relation,
                         value,
                     }),
                     action: Action::from_str(action),
-                }
+                })
             }
-            None => Step {
+            None => Ok(Step {
                 condition: None,
                 action: Action::from_str(s),
-            },
+            }),
         }
     }
 }
@@ -48,7 +70,7 @@ struct Condition {
 }
 
 impl Condition {
-    fn eval(&self, input: &Input) -> bool {
+    fn eval(&self, input: &Rating) -> bool {
         let val = match self.variable {
             Variable::Cool => input.cool,
             Variable::Musical => input.musical,
@@ -70,19 +92,24 @@ impl Condition {
             Variable::Shiny => input.shiny,
         };
 
-        let (lower_eval, upper_eval, lower_end, upper_start) = match self.relation {
-            Relation::Less => (true, false, self.value - 1, self.value),
-            Relation::Greater => (false, true, self.value, self.value + 1),
+        // The first value of the upper part, in `u32` so `> 65535` does not
+        // overflow.
+        let (lower_eval, upper_eval, split) = match self.relation {
+            Relation::Less => (true, false, self.value as u32),
+            Relation::Greater => (false, true, self.value as u32 + 1),
         };
 
-        if self.value >= upper {
+        if split > upper as u32 {
             return smallvec![(input.clone(), lower_eval)];
         }
 
-        if self.value <= lower {
+        if split <= lower as u32 {
             return smallvec![(input.clone(), upper_eval)];
         }
 
+        // `lower < split <= upper`, so both parts fit into a `u16`.
+        let (lower_end, upper_start) = (split as u16 - 1, split as u16);
+
         let mut lower_range = input.clone();
         let mut upper_range = input.clone();
 
@@ -116,11 +143,11 @@ enum Relation {
 }
 
 impl Relation {
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            '<' => Self::Less,
-            '>' => Self::Greater,
-            _ => panic!("invalid relation"),
+            '<' => Some(Self::Less),
+            '>' => Some(Self::Greater),
+            _ => None,
         }
     }
 }
@@ -134,13 +161,13 @@ enum Variable {
 }
 
 impl Variable {
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            'x' => Self::Cool,
-            'm' => Self::Musical,
-            'a' => Self::Aerodynamic,
-            's' => Self::Shiny,
-            _ => panic!("invalid variable"),
+            'x' => Some(Self::Cool),
+            'm' => Some(Self::Musical),
+            'a' => Some(Self::Aerodynamic),
+            's' => Some(Self::Shiny),
+            _ => None,
         }
     }
 }
@@ -162,51 +189,150 @@ impl Action {
     }
 }
 
-fn parse_workflows(input: &str) -> HashMap<String, Vec<Step>> {
-    WORKFLOW_REGEX
-        .captures_iter(input)
-        .map(|m| {
-            let workflow_name = m.get(1).unwrap().as_str();
-            let workflow_steps = m.get(2).unwrap().as_str();
+/// Parses the workflows and checks that every part ends up accepted or
+/// rejected: `in` exists, every target is a workflow, every workflow ends
+/// with a step without a condition and no workflow leads back to itself.
+fn parse_workflows(input: &str, workflows: &str) -> Result<HashMap<String, Vec<Step>>, ParseError> {
+    let mut parsed = HashMap::default();
+    let mut targets: HashMap<&str, Vec<&str>> = HashMap::default();
+
+    for line in workflows.lines() {
+        let m = WORKFLOW_REGEX
+            .captures(line)
+            .ok_or_else(|| ParseError::at(input, line, "invalid workflow"))?;
+
+        let workflow_name = m.get(1).unwrap().as_str();
+        let workflow_steps = m.get(2).unwrap().as_str();
+
+        let steps = workflow_steps
+            .split(',')
+            .map(|step| Step::parse(input, step))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if steps.last().unwrap().condition.is_some() {
+            let last = workflow_steps.rsplit(',').next().unwrap();
+            return Err(ParseError::at(
+                input,
+                last,
+                "the last step of a workflow cannot have a condition",
+            ));
+        }
 
-            let steps = workflow_steps
-                .split(',')
-                .map(Step::from_str)
-                .collect::<Vec<_>>();
+        let workflow_targets = workflow_steps
+            .split(',')
+            .map(|step| step.rsplit(':').next().unwrap())
+            .filter(|&target| target != "A" && target != "R")
+            .collect();
 
-            (workflow_name.to_string(), steps)
-        })
-        .collect()
+        targets.insert(workflow_name, workflow_targets);
+        parsed.insert(workflow_name.to_string(), steps);
+    }
+
+    if !parsed.contains_key("in") {
+        return Err(ParseError::at(
+            input,
+            workflows,
+            "missing the `in` workflow in",
+        ));
+    }
+
+    for &target in targets.values().flatten() {
+        if !parsed.contains_key(target) {
+            return Err(ParseError::at(input, target, "unknown workflow"));
+        }
+    }
+
+    if let Some(target) = find_loop(&targets, "in", &mut HashMap::default()) {
+        return Err(ParseError::at(
+            input,
+            target,
+            "workflow loops back to itself at",
+        ));
+    }
+
+    Ok(parsed)
+}
+
+/// Follows the targets from `workflow` depth first and returns the first one
+/// that leads back to a workflow on the current path. `done` is `false` for
+/// the workflows on the path and `true` for those without a loop.
+fn find_loop<'a>(
+    targets: &HashMap<&'a str, Vec<&'a str>>,
+    workflow: &'a str,
+    done: &mut HashMap<&'a str, bool>,
+) -> Option<&'a str> {
+    done.insert(workflow, false);
+
+    for &target in &targets[workflow] {
+        match done.get(target) {
+            Some(false) => return Some(target),
+            Some(true) => {}
+            None => {
+                if let Some(target) = find_loop(targets, target, done) {
+                    return Some(target);
+                }
+            }
+        }
+    }
+
+    done.insert(workflow, true);
+    None
 }
 
-struct Input {
+#[derive(Debug)]
+struct Rating {
     cool: u16,
     musical: u16,
     aerodynamic: u16,
     shiny: u16,
 }
 
-fn parse_input(input: &str) -> impl Iterator<Item = Input> + '_ {
-    INPUT_REGEX.captures_iter(input).map(|m| {
-        let cool = m.get(1).unwrap().as_str().parse::<u16>().unwrap();
-        let musical = m.get(2).unwrap().as_str().parse::<u16>().unwrap();
-        let aerodynamic = m.get(3).unwrap().as_str().parse::<u16>().unwrap();
-        let shiny = m.get(4).unwrap().as_str().parse::<u16>().unwrap();
-
-        Input {
-            cool,
-            musical,
-            aerodynamic,
-            shiny,
-        }
+fn parse_ratings(input: &str, parts: &str) -> Result<Vec<Rating>, ParseError> {
+    parts
+        .lines()
+        .map(|line| {
+            let m = INPUT_REGEX
+                .captures(line)
+                .ok_or_else(|| ParseError::at(input, line, "invalid part"))?;
+
+            let cool = number(input, m.get(1).unwrap().as_str())?;
+            let musical = number(input, m.get(2).unwrap().as_str())?;
+            let aerodynamic = number(input, m.get(3).unwrap().as_str())?;
+            let shiny = number(input, m.get(4).unwrap().as_str())?;
+
+            Ok(Rating {
+                cool,
+                musical,
+                aerodynamic,
+                shiny,
+            })
+        })
+        .collect()
+}
+
+/// The workflows and the ratings of the parts to sort.
+#[derive(Debug)]
+pub struct Input {
+    workflows: HashMap<String, Vec<Step>>,
+    ratings: Vec<Rating>,
+}
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let (workflows, ratings) = split_once(input, input, "\n\n")?;
+
+    Ok(Input {
+        workflows: parse_workflows(input, workflows)?,
+        ratings: parse_ratings(input, ratings)?,
     })
 }
 
-fn process(workflows: &HashMap<String, Vec<Step>>, input: &Input) -> bool {
+fn process(workflows: &HashMap<String, Vec<Step>>, input: &Rating) -> bool {
     let mut current_workflow = "in";
 
     loop {
-        let steps = workflows.get(current_workflow).unwrap();
+        // `parse` made sure the workflow exists and ends without a
+        // condition, so every workflow either sends the part on or decides.
+        let steps = &workflows[current_workflow];
 
         for step in steps {
             if let Some(condition) = &step.condition {
@@ -258,7 +384,7 @@ fn find_accepting_ranges(workflows: &HashMap<String, Vec<Step>>) -> Vec<InputRan
     let mut unterminated_inputs = vec![state];
 
     while let Some(input) = unterminated_inputs.pop() {
-        let step = &workflows.get(input.workflow).unwrap()[input.step as usize];
+        let step = &workflows[input.workflow][input.step as usize];
 
         let mut apply_action = |range: InputRange, unterminated: &mut Vec<_>| match &step.action {
             Action::Accept => accepted_ranges.push(range),
@@ -294,50 +420,91 @@ fn find_accepting_ranges(workflows: &HashMap<String, Vec<Step>>) -> Vec<InputRan
     accepted_ranges
 }
 
-pub fn part_one(input: &str) -> usize {
-    let (workflows, inputs) = input.split_once("\n\n").unwrap();
-
-    let inputs = parse_input(inputs);
-    let workflows = parse_workflows(workflows);
-
-    inputs
-        .filter(|input| process(&workflows, input))
-        .flat_map(|input| [input.cool, input.musical, input.aerodynamic, input.shiny])
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    let Input { workflows, ratings } = input;
+
+    Ok(ratings
+        .iter()
+        .filter(|rating| process(workflows, rating))
+        .flat_map(|rating| {
+            [
+                rating.cool,
+                rating.musical,
+                rating.aerodynamic,
+                rating.shiny,
+            ]
+        })
         .map(|x| x as usize)
         .sum::<usize>()
+        .into())
 }
 
-pub fn part_two(input: &str) -> u128 {
-    let (workflows, _) = input.split_once("\n\n").unwrap();
-    let workflows = parse_workflows(workflows);
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    let mut combinations = 0u128;
 
-    let mut combinations = 0;
-
-    for range in find_accepting_ranges(&workflows) {
+    for range in find_accepting_ranges(&input.workflows) {
         combinations += (range.cool.1 - range.cool.0 + 1) as u128
             * (range.musical.1 - range.musical.0 + 1) as u128
             * (range.aerodynamic.1 - range.aerodynamic.0 + 1) as u128
             * (range.shiny.1 - range.shiny.0 + 1) as u128;
     }
 
-    combinations
+    Ok(combinations.into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::read_example;
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 19);
-        assert_eq!(part_one(&input), 19114);
+        let input = parse(&read_example(19, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 19114);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 19);
-        assert_eq!(part_two(&input), 167409079868000);
+        let input = parse(&read_example(19, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 167409079868000u64);
+
+        // Values on the bounds of the range only split off a single rating.
+        let input = parse("in{x<4000:R,m>1:R,A}\n\n{x=1,m=1,a=1,s=1}").unwrap();
+        assert_eq!(part_two(&input).unwrap(), 4000u64 * 4000);
+
+        let input = parse("in{x>65535:R,a<1:R,A}\n\n{x=1,m=1,a=1,s=1}").unwrap();
+        assert_eq!(part_two(&input).unwrap(), 4000u64.pow(4));
+    }
+
+    #[test]
+    fn test_invalid_workflows() {
+        let part = "\n\n{x=1,m=1,a=1,s=1}";
+        let error = |workflows: &str| {
+            let err = parse(&format!("{}{}", workflows, part)).unwrap_err();
+            (err.line, err.column, err.text, err.message)
+        };
+
+        let (line, _, _, message) = error("px{a<1:A,R}");
+        assert_eq!(
+            (line, message.as_str()),
+            (1, "missing the `in` workflow in")
+        );
+
+        let (_, column, text, message) = error("in{a<1:A,px}");
+        assert_eq!(
+            (column, text.as_str(), message.as_str()),
+            (10, "px", "unknown workflow")
+        );
+
+        let (_, column, text, _) = error("in{x<5:A}");
+        assert_eq!((column, text.as_str()), (4, "x<5:A"));
+
+        let (_, column, text, _) = error("in{x<0:A,R}");
+        assert_eq!((column, text.as_str()), (6, "0"));
+
+        let (line, _, text, message) = error("in{a<5:px,R}\npx{qq}\nqq{x>3:in,A}");
+        assert_eq!((line, text.as_str()), (3, "in"));
+        assert_eq!(message, "workflow loops back to itself at");
     }
 }
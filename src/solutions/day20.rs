@@ -1,6 +1,17 @@
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    io::{self, Write},
+};
 
-#[derive(Debug)]
+use bitvec::vec::BitVec;
+use rustc_hash::FxHashMap;
+
+use crate::parse::split_once;
+use crate::search::bfs_reach;
+use crate::{Answer, ParseError, SolveError};
+
+#[derive(Debug, Clone)]
 enum ModuleType {
     FlipFlop(bool),
     Conjunction(Vec<PulseType>),
@@ -36,40 +47,103 @@ impl ModuleType {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct ModuleIO {
     output: Vec<String>,
     input: Vec<String>,
 }
 
-#[derive(Debug, Clone, Copy)]
-enum PulseType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PulseType {
     Low,
     High,
 }
 
-#[derive(Debug)]
-struct Circuit {
+/// A pulse sent from one module to another while pushing the button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pulse<'a> {
+    pub sender: &'a str,
+    pub receiver: &'a str,
+    pub pulse: PulseType,
+}
+
+impl fmt::Display for Pulse<'_> {
+    /// Formats the pulse like the puzzle does, e.g. `a -high-> b`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pulse = match self.pulse {
+            PulseType::Low => "low",
+            PulseType::High => "high",
+        };
+
+        write!(f, "{} -{}-> {}", self.sender, pulse, self.receiver)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Circuit {
     modules: HashMap<String, ModuleType>,
     io: HashMap<String, ModuleIO>,
+    /// The module names in sorted order, so snapshots of circuits parsed
+    /// from the same input line up.
+    order: Vec<String>,
+}
+
+/// A snapshot of the state of every module of a [`Circuit`].
+///
+/// One bit per flip-flop and one bit per input of every conjunction, set when
+/// the flip-flop is on or the last pulse from that input was high.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CircuitState(BitVec);
+
+/// The modules below one output of the broadcaster, up to the conjunction
+/// feeding `rx`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubCircuit {
+    /// The module the broadcaster sends its pulses to.
+    pub start: String,
+    /// Every module of the sub-circuit, sorted by name.
+    pub modules: Vec<String>,
+}
+
+/// A sub-circuit working as a binary counter.
+///
+/// The flip-flops form a chain, each one toggling the next when it turns
+/// off, so together they count the button presses. The flip-flops of the
+/// set bits of `period` are wired into the conjunction. Once they are all on,
+/// it resets the counter by toggling the others and the first one, and its
+/// inverter sends a high pulse to the `rx` feeder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter {
+    /// The flip-flops, lowest bit first.
+    pub flip_flops: Vec<String>,
+    pub conjunction: String,
+    pub inverter: String,
+    /// Every how many presses the counter resets.
+    pub period: u64,
+}
+
+/// The presses after which a circuit is back in a state it was in before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// The presses before the circuit enters the cycle.
+    pub start: usize,
+    /// The presses after which the state repeats once in the cycle.
+    pub length: usize,
 }
 
 impl Circuit {
-    fn from_input(input: &str) -> Self {
+    fn from_input(input: &str) -> Result<Self, ParseError> {
         let mut modules_map = HashMap::new();
         let mut io_map = HashMap::new();
 
         for line in input.lines() {
-            let (name, outputs) = line.split_once(" -> ").unwrap();
+            let (name, outputs) = split_once(input, line, " -> ")?;
 
-            let (r#type, name) = {
-                let first_char = name.chars().next().unwrap();
-
-                match first_char {
-                    '%' => (ModuleType::FlipFlop(false), name[1..].to_string()),
-                    '&' => (ModuleType::Conjunction(Vec::new()), name[1..].to_string()),
-                    _ => (ModuleType::Broadcast, name.to_string()),
-                }
+            let (r#type, name) = match name.chars().next() {
+                Some('%') => (ModuleType::FlipFlop(false), name[1..].to_string()),
+                Some('&') => (ModuleType::Conjunction(Vec::new()), name[1..].to_string()),
+                Some(_) => (ModuleType::Broadcast, name.to_string()),
+                None => return Err(ParseError::at(input, line, "missing module name in")),
             };
 
             let output = outputs.split(", ").map(|s| s.to_string()).collect();
@@ -91,66 +165,157 @@ impl Circuit {
             let outputs = io_map[&key].output.clone();
 
             for output in outputs {
-                match io_map.get_mut(&output) {
-                    Some(module) => {
-                        module.input.push(key.clone());
-
-                        let mut r#type = modules_map.get_mut(&output).unwrap();
-                        if let ModuleType::Conjunction(s) = &mut r#type {
-                            s.push(PulseType::Low); // init with low
-                        }
-                    }
-                    None => continue,
+                // Modules that only receive pulses, like `rx`, are not
+                // declared but still need to know their inputs.
+                let module = io_map.entry(output.clone()).or_insert_with(|| ModuleIO {
+                    output: Vec::new(),
+                    input: Vec::new(),
+                });
+                module.input.push(key.clone());
+
+                if let Some(ModuleType::Conjunction(s)) = modules_map.get_mut(&output) {
+                    s.push(PulseType::Low); // init with low
                 }
             }
         }
 
         io_map
             .get_mut("broadcaster")
-            .unwrap()
+            .ok_or_else(|| ParseError::end_of_input(input, "no broadcaster module"))?
             .input
             .push("button".to_string());
 
-        Self {
+        let mut order: Vec<_> = modules_map.keys().cloned().collect();
+        order.sort();
+
+        Ok(Self {
             modules: modules_map,
             io: io_map,
+            order,
+        })
+    }
+
+    /// The current state of all modules.
+    pub fn state(&self) -> CircuitState {
+        let mut bits = BitVec::new();
+
+        for name in &self.order {
+            match &self.modules[name] {
+                ModuleType::FlipFlop(on) => bits.push(*on),
+                ModuleType::Conjunction(last_signals) => {
+                    bits.extend(last_signals.iter().map(|&p| p == PulseType::High))
+                }
+                ModuleType::Broadcast => {}
+            }
+        }
+
+        CircuitState(bits)
+    }
+
+    /// Pushes the button until the circuit gets back to a state it was in
+    /// before, at most `max_presses` times. `on_press` gets the low and high
+    /// pulses of every press.
+    ///
+    /// Returns `None` if no state repeated within `max_presses`.
+    pub fn find_cycle(
+        &mut self,
+        max_presses: usize,
+        mut on_press: impl FnMut((usize, usize)),
+    ) -> Option<Cycle> {
+        let mut seen = FxHashMap::default();
+        seen.insert(self.state(), 0);
+
+        for presses in 1..=max_presses {
+            on_press(self.push_button());
+
+            if let Some(start) = seen.insert(self.state(), presses) {
+                return Some(Cycle {
+                    start,
+                    length: presses - start,
+                });
+            }
         }
+
+        None
+    }
+
+    /// The low and high pulses sent during `presses` button presses.
+    ///
+    /// Once the state repeats, the rest of the presses are counted from the
+    /// cycle instead of being simulated, so huge counts work as long as the
+    /// circuit cycles within [`MAX_PRESSES`]. The counts are `u128`, which
+    /// holds `u64::MAX` presses of up to `u64::MAX` pulses each.
+    pub fn count_pulses(&self, presses: u64) -> Result<(u128, u128), SolveError> {
+        let mut circuit = self.clone();
+        let mut pulses = Vec::new();
+        let max_presses = presses.min(MAX_PRESSES as u64) as usize;
+
+        let cycle = circuit.find_cycle(max_presses, |(low, high)| {
+            pulses.push((low as u128, high as u128));
+        });
+
+        let sum = |pulses: &[(u128, u128)]| {
+            pulses
+                .iter()
+                .fold((0, 0), |(low, high), &(l, h)| (low + l, high + h))
+        };
+
+        let Some(Cycle { start, length }) = cycle else {
+            if pulses.len() as u64 == presses {
+                return Ok(sum(&pulses));
+            }
+
+            return Err(SolveError(format!(
+                "the circuit does not repeat within {} presses",
+                MAX_PRESSES
+            )));
+        };
+
+        let (before, cycle) = pulses.split_at(start);
+        let remaining = presses - start as u64;
+        let (full, rest) = (remaining / length as u64, remaining % length as u64);
+        let full = full as u128;
+
+        let (low, high) = sum(before);
+        let (cycle_low, cycle_high) = sum(cycle);
+        let (rest_low, rest_high) = sum(&cycle[..rest as usize]);
+
+        Ok((
+            low + full * cycle_low + rest_low,
+            high + full * cycle_high + rest_high,
+        ))
+    }
+
+    fn push_button(&mut self) -> (usize, usize) {
+        self.push_button_with(|_| {})
     }
 
-    fn push_button(&mut self) -> (usize, usize, [usize; 4]) {
+    /// Pushes the button once, calling `on_pulse` for every pulse in the
+    /// order they are sent. Returns the number of low and high pulses.
+    pub fn push_button_with(&mut self, mut on_pulse: impl FnMut(Pulse)) -> (usize, usize) {
         let mut actions = VecDeque::new();
         actions.push_back(("broadcaster", "button", PulseType::Low));
 
         let mut low_pulses = 0;
         let mut high_pulses = 0;
 
-        let mut target_triggers = [0, 0, 0, 0];
-
         while let Some((name, sender, pulse)) = actions.pop_front() {
             match pulse {
                 PulseType::Low => low_pulses += 1,
                 PulseType::High => high_pulses += 1,
             };
 
+            on_pulse(Pulse {
+                sender,
+                receiver: name,
+                pulse,
+            });
+
             let module = match self.modules.get_mut(name) {
                 Some(m) => m,
                 None => continue,
             };
 
-            // if name == "rx" && matches!(pulse, PulseType::Low) {
-            //     rx_triggers += 1;
-            // }
-
-            if name == "hf" {
-                if let ModuleType::Conjunction(s) = module {
-                    for (i, p) in s.iter().enumerate() {
-                        if matches!(p, PulseType::High) {
-                            target_triggers[i] += 1;
-                        }
-                    }
-                }
-            }
-
             let io = &self.io[name];
 
             let input_idx = io.input.iter().position(|s| s == sender).unwrap_or(0);
@@ -163,78 +328,571 @@ impl Circuit {
             }
         }
 
-        (low_pulses, high_pulses, target_triggers)
+        (low_pulses, high_pulses)
     }
-}
 
-pub fn part_one(input: &str) -> usize {
-    let mut circuit = Circuit::from_input(input);
-    println!("{:?}", circuit);
-    let (mut total_low, mut total_high) = (0, 0);
+    /// The circuit as a Graphviz DOT graph.
+    ///
+    /// Modules are labelled like in the puzzle input, flip-flops are boxes,
+    /// conjunctions diamonds and the broadcaster a double circle. Modules that
+    /// only receive pulses, like `rx`, are plain ellipses.
+    pub fn to_dot(&self) -> String {
+        let mut names: Vec<_> = self.io.keys().collect();
+        names.sort();
+
+        let mut dot = String::from("digraph circuit {\n");
+
+        for name in &names {
+            let (shape, prefix) = match self.modules.get(*name) {
+                Some(ModuleType::FlipFlop(_)) => ("box", "%"),
+                Some(ModuleType::Conjunction(_)) => ("diamond", "&"),
+                Some(ModuleType::Broadcast) => ("doublecircle", ""),
+                None => ("ellipse", ""),
+            };
+
+            dot += &format!(
+                "    \"{}\" [shape={}, label=\"{}{}\"];\n",
+                name, shape, prefix, name
+            );
+        }
+
+        dot += "\n";
+
+        for name in &names {
+            for output in &self.io[*name].output {
+                dot += &format!("    \"{}\" -> \"{}\";\n", name, output);
+            }
+        }
 
-    for _ in 0..1000 {
-        let (low, high, _) = circuit.push_button();
-        total_high += high;
-        total_low += low;
+        dot += "}\n";
+        dot
     }
 
-    println!("total_low: {}, total_high: {}", total_low, total_high);
+    /// The conjunction sending pulses to `rx` and the modules feeding it.
+    fn rx_feeder(&self) -> Result<(&str, &[String]), SolveError> {
+        let rx = self
+            .io
+            .get("rx")
+            .ok_or_else(|| SolveError("the circuit has no rx module".to_string()))?;
+
+        let [feeder] = &rx.input[..] else {
+            return Err(SolveError(format!(
+                "rx has {} inputs, expected a single conjunction",
+                rx.input.len()
+            )));
+        };
+
+        match self.modules.get(feeder) {
+            Some(ModuleType::Conjunction(_)) => Ok((feeder, &self.io[feeder].input)),
+            _ => Err(SolveError(format!(
+                "`{}` feeding rx is not a conjunction",
+                feeder
+            ))),
+        }
+    }
 
-    total_high * total_low
-}
+    /// Splits the circuit into the parts below each output of the
+    /// broadcaster, which must not share any modules.
+    pub fn sub_circuits(&self) -> Result<Vec<SubCircuit>, SolveError> {
+        let (feeder, _) = self.rx_feeder()?;
+        let mut owners: HashMap<&str, &str> = HashMap::new();
+        let mut sub_circuits = Vec::new();
+
+        for start in &self.io["broadcaster"].output {
+            let outputs = |name: &&str| {
+                self.io[*name]
+                    .output
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|&output| output != feeder)
+                    .collect::<Vec<_>>()
+            };
 
-pub fn part_two(input: &str) -> usize {
-    let mut circuit = Circuit::from_input(input);
-    println!("{:?}", circuit);
-    let mut presses = 0;
+            let mut modules = Vec::new();
 
-    let mut cycles = [None; 4];
+            for (name, _) in bfs_reach(start.as_str(), outputs) {
+                if let Some(owner) = owners.insert(name, start) {
+                    return Err(SolveError(format!(
+                        "`{}` is shared by the sub-circuits of `{}` and `{}`",
+                        name, owner, start
+                    )));
+                }
 
-    loop {
-        presses += 1;
+                modules.push(name.to_string());
+            }
 
-        if presses % 100_000 == 0 {
-            println!("i: {}", presses);
+            modules.sort();
+            sub_circuits.push(SubCircuit {
+                start: start.clone(),
+                modules,
+            });
         }
 
-        let (_, _, rx_triggers) = circuit.push_button();
+        Ok(sub_circuits)
+    }
+
+    /// Recognises `sub` as a binary counter and reads its period from the
+    /// wiring of its flip-flops.
+    pub fn counter(&self, sub: &SubCircuit) -> Result<Counter, SolveError> {
+        let not_a_counter = |reason: String| {
+            SolveError(format!(
+                "the sub-circuit of `{}` is not a counter: {}",
+                sub.start, reason
+            ))
+        };
+
+        let is_flip_flop =
+            |name: &str| matches!(self.modules.get(name), Some(ModuleType::FlipFlop(_)));
+        let is_conjunction =
+            |name: &str| matches!(self.modules.get(name), Some(ModuleType::Conjunction(_)));
+
+        let mut flip_flops: Vec<&str> = Vec::new();
+        let mut conjunction = None;
+        let mut period = 0;
+        let mut next = Some(sub.start.as_str());
+
+        while let Some(name) = next.take() {
+            if !is_flip_flop(name) || flip_flops.contains(&name) {
+                return Err(not_a_counter(format!("`{}` is not a new flip-flop", name)));
+            }
 
-        for (i, t) in rx_triggers.iter().enumerate() {
-            if *t > 0 && cycles[i].is_none() {
-                cycles[i] = Some(presses);
+            if flip_flops.len() == u64::BITS as usize {
+                return Err(not_a_counter(format!(
+                    "it has more than {} flip-flops",
+                    u64::BITS
+                )));
+            }
+
+            flip_flops.push(name);
+
+            for output in &self.io[name].output {
+                if is_flip_flop(output) && next.is_none() {
+                    next = Some(output);
+                } else if is_conjunction(output) && conjunction.is_none_or(|c| c == output) {
+                    conjunction = Some(output.as_str());
+                    period |= 1 << (flip_flops.len() - 1);
+                } else {
+                    return Err(not_a_counter(format!(
+                        "unexpected output `{}` of `{}`",
+                        output, name
+                    )));
+                }
+            }
+        }
+
+        let conjunction = conjunction.ok_or_else(|| not_a_counter("no conjunction".to_string()))?;
+
+        // A low pulse from the conjunction turns the flip-flops of the zero
+        // bits and the first one on, which carries the counter back to zero.
+        let mut resets: Vec<_> = flip_flops
+            .iter()
+            .enumerate()
+            .filter(|&(bit, _)| bit == 0 || period & (1 << bit) == 0)
+            .map(|(_, name)| name.to_string())
+            .collect();
+
+        let mut inverter = None;
+
+        for output in &self.io[conjunction].output {
+            if let Some(idx) = resets.iter().position(|name| name == output) {
+                resets.swap_remove(idx);
+            } else if inverter.is_none()
+                && is_conjunction(output)
+                && self.io[output].input.len() == 1
+            {
+                inverter = Some(output.clone());
+            } else {
+                return Err(not_a_counter(format!(
+                    "unexpected output `{}` of `{}`",
+                    output, conjunction
+                )));
+            }
+        }
+
+        if let Some(name) = resets.first() {
+            return Err(not_a_counter(format!(
+                "`{}` does not reset `{}`",
+                conjunction, name
+            )));
+        }
+
+        let inverter =
+            inverter.ok_or_else(|| not_a_counter(format!("`{}` has no inverter", conjunction)))?;
+
+        if sub.modules.len() != flip_flops.len() + 2 {
+            return Err(not_a_counter(
+                "it has modules besides the counter".to_string(),
+            ));
+        }
+
+        Ok(Counter {
+            flip_flops: flip_flops.into_iter().map(String::from).collect(),
+            conjunction: conjunction.to_string(),
+            inverter,
+            period,
+        })
+    }
+
+    /// The counters under the broadcaster, with the inverters of all of them
+    /// feeding `rx`.
+    pub fn counters(&self) -> Result<Vec<Counter>, SolveError> {
+        let (feeder, inputs) = self.rx_feeder()?;
+        let counters = self
+            .sub_circuits()?
+            .iter()
+            .map(|sub| self.counter(sub))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for input in inputs {
+            if !counters.iter().any(|counter| &counter.inverter == input) {
+                return Err(SolveError(format!(
+                    "`{}` feeding `{}` is not the inverter of a counter",
+                    input, feeder
+                )));
             }
         }
 
-        println!("rx_triggers: {:?}", rx_triggers);
-        println!("cycles: {:?}", cycles);
+        Ok(counters)
+    }
+}
+
+/// The circuit with all modules in their initial state.
+pub type Input = Circuit;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    Circuit::from_input(input)
+}
+
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    let (low, high) = input.count_pulses(1000)?;
+    Ok((low * high).into())
+}
+
+/// Writes every pulse of `presses` button presses, starting from the state of
+/// `circuit`, to `out`. Returns the number of pulses.
+pub fn write_trace(circuit: &Circuit, presses: usize, mut out: impl Write) -> io::Result<usize> {
+    let mut circuit = circuit.clone();
+    let mut pulses = 0;
 
-        if cycles.iter().all(|c| c.is_some()) {
-            let mut cycle = 1;
+    for press in 1..=presses {
+        writeln!(out, "press {}", press)?;
+        let mut result = Ok(());
 
-            for c in cycles.iter() {
-                cycle = num::integer::lcm(cycle, c.unwrap());
+        circuit.push_button_with(|pulse| {
+            if result.is_ok() {
+                result = writeln!(out, "{}", pulse);
+                pulses += 1;
             }
+        });
 
-            return cycle;
+        result?;
+    }
+
+    out.flush()?;
+    Ok(pulses)
+}
+
+/// How many presses to wait for the circuit, or the inputs of the `rx`
+/// feeder, to repeat.
+pub const MAX_PRESSES: usize = 100_000;
+
+/// `rx` gets a low pulse once every input of the conjunction feeding it sent
+/// a high pulse during the same press. Each input is the inverter of a
+/// counter, which does that every time the counter resets, so the first press
+/// they all line up is the least common multiple of their periods.
+///
+/// Circuits that are not built from counters are simulated instead. If that
+/// fails too, the error also tells why the circuit is not made of counters.
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    input.rx_feeder()?;
+
+    let counters = match input.counters() {
+        Ok(counters) => counters,
+        Err(not_counters) => {
+            return simulate_part_two(input)
+                .map(Answer::from)
+                .map_err(|err| SolveError(format!("{} ({})", err.0, not_counters.0)))
         }
+    };
+
+    let cycle = counters
+        .iter()
+        .fold(1, |cycle, counter| num::integer::lcm(cycle, counter.period));
+
+    Ok(cycle.into())
+}
+
+/// Finds the answer of part two by pushing the button instead of analysing
+/// the circuit. Works on any circuit, as long as the inputs of the `rx`
+/// feeder repeat within [`MAX_PRESSES`].
+///
+/// Each input of the conjunction feeding `rx` is expected to send a high
+/// pulse every `n` presses, starting with press `n`.
+pub fn simulate_part_two(input: &Input) -> Result<u64, SolveError> {
+    let (feeder, inputs) = input.rx_feeder()?;
+    let mut circuit = input.clone();
+    let mut high_presses = vec![Vec::new(); inputs.len()];
+
+    for presses in 1..=MAX_PRESSES {
+        circuit.push_button_with(|pulse| {
+            if pulse.receiver != feeder || pulse.pulse != PulseType::High {
+                return;
+            }
+
+            let sender = inputs.iter().position(|s| s == pulse.sender).unwrap();
+            let seen = &mut high_presses[sender];
+
+            if seen.len() < 2 && seen.last() != Some(&presses) {
+                seen.push(presses);
+            }
+        });
+
+        if high_presses.iter().all(|seen| seen.len() == 2) {
+            break;
+        }
+    }
+
+    let mut cycle = 1;
+
+    for (sender, seen) in inputs.iter().zip(&high_presses) {
+        let &[first, second] = &seen[..] else {
+            return Err(SolveError(format!(
+                "`{}` did not send two high pulses to `{}` within {} presses",
+                sender, feeder, MAX_PRESSES
+            )));
+        };
+
+        if second - first != first {
+            return Err(SolveError(format!(
+                "`{}` first sends a high pulse to `{}` on press {} but then every {} presses, \
+                 so the cycles cannot be combined with an LCM",
+                sender,
+                feeder,
+                first,
+                second - first
+            )));
+        }
+
+        cycle = num::integer::lcm(cycle, first as u64);
     }
+
+    Ok(cycle)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::{read_example, read_file};
 
     use super::*;
 
+    #[test]
+    fn test_trace() {
+        let input = parse(&read_example(20, "example")).unwrap();
+        let mut trace = Vec::new();
+        assert_eq!(write_trace(&input, 2, &mut trace).unwrap(), 24);
+
+        let trace = String::from_utf8(trace).unwrap();
+        let first_press: Vec<_> = trace.lines().take(13).collect();
+        assert_eq!(
+            first_press,
+            [
+                "press 1",
+                "button -low-> broadcaster",
+                "broadcaster -low-> a",
+                "broadcaster -low-> b",
+                "broadcaster -low-> c",
+                "a -high-> b",
+                "b -high-> c",
+                "c -high-> inv",
+                "inv -low-> a",
+                "a -low-> b",
+                "b -low-> c",
+                "c -low-> inv",
+                "inv -high-> a",
+            ]
+        );
+        assert_eq!(trace.lines().nth(13), Some("press 2"));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let input = parse(&read_example(20, "example2")).unwrap();
+        let dot = input.to_dot();
+
+        assert!(dot.starts_with("digraph circuit {\n    \"a\" [shape=box, label=\"%a\"];\n"));
+        assert!(dot.contains("    \"broadcaster\" [shape=doublecircle, label=\"broadcaster\"];\n"));
+        assert!(dot.contains("    \"con\" [shape=diamond, label=\"&con\"];\n"));
+        assert!(dot.contains("    \"output\" [shape=ellipse, label=\"output\"];\n"));
+        assert!(dot.contains("    \"inv\" -> \"b\";\n"));
+        assert_eq!(dot.matches(" -> ").count(), 6);
+        assert!(dot.ends_with("}\n"));
+    }
+
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 20);
-        assert_eq!(part_one(&input), 32000000);
+        let input = parse(&read_example(20, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 32000000);
+
+        let input = parse(&read_example(20, "example2")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 11687500);
+    }
+
+    #[test]
+    fn test_cycle() {
+        let mut input = parse(&read_example(20, "example")).unwrap();
+        let initial = input.state();
+        assert_eq!(
+            parse(&read_example(20, "example")).unwrap().state(),
+            initial
+        );
+
+        let mut presses = 0;
+        let cycle = input.find_cycle(10, |_| presses += 1);
+        assert_eq!(
+            cycle,
+            Some(Cycle {
+                start: 0,
+                length: 1
+            })
+        );
+        assert_eq!((presses, input.state()), (1, initial));
+
+        let mut input = parse(&read_example(20, "example2")).unwrap();
+        let initial = input.state();
+        input.push_button();
+        assert_ne!(input.state(), initial);
+        assert_eq!(input.clone().state(), input.state());
+
+        let cycle = input.find_cycle(10, |_| {});
+        assert_eq!(
+            cycle,
+            Some(Cycle {
+                start: 0,
+                length: 4
+            })
+        );
+        assert_eq!(input.find_cycle(3, |_| {}), None);
+    }
+
+    #[test]
+    fn test_count_pulses() {
+        let input = parse(&read_example(20, "example")).unwrap();
+        assert_eq!(input.count_pulses(1000).unwrap(), (8000, 4000));
+
+        let max = u64::MAX as u128;
+        assert_eq!(input.count_pulses(u64::MAX).unwrap(), (8 * max, 4 * max));
+
+        let input = parse(&read_example(20, "example2")).unwrap();
+        assert_eq!(input.count_pulses(1000).unwrap(), (4250, 2750));
+        assert_eq!(
+            input.count_pulses(1_000_000_000).unwrap(),
+            (4_250_000_000, 2_750_000_000)
+        );
+
+        // The counters only return to their initial state after 15 presses.
+        let input = parse(COUNTERS).unwrap();
+        let mut circuit = input.clone();
+        let (mut low, mut high) = (0, 0);
+
+        for _ in 0..1003 {
+            let pulses = circuit.push_button();
+            low += pulses.0 as u128;
+            high += pulses.1 as u128;
+        }
+
+        assert_eq!(input.count_pulses(1003).unwrap(), (low, high));
+        assert_eq!(
+            input.clone().find_cycle(100, |_| {}).map(|c| c.length),
+            Some(15)
+        );
+    }
+
+    /// Two counters with a cycle of 3 and 5 presses feeding `rx`.
+    const COUNTERS: &str = "\
+broadcaster -> a0, b0
+%a0 -> a1, ca
+%a1 -> ca
+&ca -> a0, ia
+&ia -> hub
+%b0 -> b1, cb
+%b1 -> b2
+%b2 -> cb
+&cb -> b0, b1, ib
+&ib -> hub
+&hub -> rx";
+
+    #[test]
+    fn test_counters() {
+        let input = parse(COUNTERS).unwrap();
+        let sub_circuits = input.sub_circuits().unwrap();
+        assert_eq!(sub_circuits[0].start, "a0");
+        assert_eq!(sub_circuits[0].modules, ["a0", "a1", "ca", "ia"]);
+
+        let counter = input.counter(&sub_circuits[1]).unwrap();
+        assert_eq!(counter.flip_flops, ["b0", "b1", "b2"]);
+        assert_eq!(
+            (counter.conjunction.as_str(), counter.inverter.as_str()),
+            ("cb", "ib")
+        );
+        assert_eq!(counter.period, 0b101);
+
+        let periods: Vec<_> = input.counters().unwrap().iter().map(|c| c.period).collect();
+        assert_eq!(periods, [3, 5]);
+
+        // `cb` no longer turns `b1` on, so the counter does not reset.
+        let input = parse(&COUNTERS.replace("&cb -> b0, b1, ib", "&cb -> b0, ib")).unwrap();
+        let err = input.counters().unwrap_err();
+        assert_eq!(
+            err.0,
+            "the sub-circuit of `b0` is not a counter: `cb` does not reset `b1`"
+        );
+
+        // The period of a chain of 66 flip-flops does not fit into a `u64`.
+        let mut long = String::from("broadcaster -> f0\n");
+        for bit in 0..65 {
+            long += &format!("%f{} -> f{}, c\n", bit, bit + 1);
+        }
+        long += "%f65 -> c\n&c -> f0, i\n&i -> hub\n&hub -> rx";
+        let input = parse(&long).unwrap();
+        let err = input.counters().unwrap_err();
+        assert_eq!(
+            err.0,
+            "the sub-circuit of `f0` is not a counter: it has more than 64 flip-flops"
+        );
+
+        let input = parse(&COUNTERS.replace("%b2 -> cb", "%b2 -> cb, a1")).unwrap();
+        let err = input.sub_circuits().unwrap_err();
+        assert_eq!(err.0, "`a1` is shared by the sub-circuits of `a0` and `b0`");
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 20);
-        assert_eq!(part_two(&input), 0);
+        let input = parse(COUNTERS).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 15);
+        assert_eq!(simulate_part_two(&input).unwrap(), 15);
+
+        let input = parse(&read_file("inputs", 20)).unwrap();
+        assert_eq!(
+            part_two(&input).unwrap(),
+            simulate_part_two(&input).unwrap()
+        );
+
+        // `log` keeps `ca` from being recognised, so the presses are simulated.
+        let input = parse(&COUNTERS.replace("&ca -> a0, ia", "&ca -> a0, ia, log")).unwrap();
+        assert!(input.counters().is_err());
+        assert_eq!(part_two(&input).unwrap(), 15);
+
+        let input = parse(&COUNTERS.replace("&cb -> b0, b1, ib", "&cb -> b0, ib")).unwrap();
+        let err = part_two(&input).unwrap_err();
+        assert!(err
+            .0
+            .ends_with("(the sub-circuit of `b0` is not a counter: `cb` does not reset `b1`)"));
+
+        let input = parse(&read_example(20, "example")).unwrap();
+        let err = part_two(&input).unwrap_err();
+        assert_eq!(err.0, "the circuit has no rx module");
+
+        let input = parse(&COUNTERS.replace("&hub -> rx", "%hub -> rx")).unwrap();
+        assert!(part_two(&input).is_err());
     }
 }
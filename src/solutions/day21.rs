@@ -2,6 +2,10 @@ use rustc_hash::FxHashSet as HashSet;
 
 use grid::Grid;
 
+use crate::geom::{Direction4, Point};
+use crate::grid::parse_grid;
+use crate::{Answer, ParseError, SolveError};
+
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
 enum Cell {
     Starting,
@@ -9,77 +13,61 @@ enum Cell {
     Rocks,
 }
 
-impl Cell {
-    fn from_char(c: char) -> Self {
+impl TryFrom<char> for Cell {
+    type Error = &'static str;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            'S' => Self::Starting,
-            '.' => Self::Plots,
-            '#' => Self::Rocks,
-            _ => panic!("invalid cell"),
+            'S' => Ok(Self::Starting),
+            '.' => Ok(Self::Plots),
+            '#' => Ok(Self::Rocks),
+            _ => Err("invalid cell"),
         }
     }
 }
 
-fn parse_grid(input: &str) -> (Grid<Cell>, (usize, usize)) {
-    let mut cols: Option<usize> = None;
-    let mut starting_pos: Option<(usize, usize)> = None;
-    let mut data = Vec::new();
-
-    for (y, line) in input.lines().enumerate() {
-        if y == 0 {
-            cols = Some(line.len());
-        }
-
-        for (x, c) in line.chars().enumerate() {
-            let s = Cell::from_char(c);
+/// The garden and the position the elf starts from.
+pub struct Input {
+    grid: Grid<Cell>,
+    starting_pos: (usize, usize),
+}
 
-            if matches!(s, Cell::Starting) {
-                starting_pos = Some((y, x));
-            }
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let parsed = parse_grid(input, &['S'])?;
+    let starting_pos = parsed
+        .marker('S')
+        .ok_or_else(|| ParseError::end_of_input(input, "no starting pos"))?;
 
-            data.push(s);
-        }
-    }
-
-    (
-        Grid::from_vec(data, cols.expect("no data")),
-        starting_pos.expect("no starting pos"),
-    )
+    Ok(Input {
+        grid: parsed.grid,
+        starting_pos,
+    })
 }
 
-pub fn part_one(input: &str) -> usize {
-    let (grid, starting_pos) = parse_grid(input);
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    let Input { grid, starting_pos } = input;
     let mut frontier = HashSet::default();
-    frontier.insert(starting_pos);
+    frontier.insert(Point::from(*starting_pos));
 
     for _ in 0..64 {
         let mut next_frontier = HashSet::default();
 
-        for (y, x) in frontier {
-            for (dy, dx) in &[(0, 1), (0, -1), (1, 0), (-1, 0)] {
-                let (Some(ny), Some(nx)) = (y.checked_add_signed(*dy), x.checked_add_signed(*dx))
-                else {
-                    continue;
-                };
-
-                match grid.get(ny, nx) {
-                    Some(Cell::Rocks) => continue,
-                    None => continue,
-                    _ => (),
+        for position in frontier {
+            for (_, next) in position.neighbours(grid) {
+                if !matches!(grid[next], Cell::Rocks) {
+                    next_frontier.insert(next);
                 }
-
-                next_frontier.insert((ny, nx));
             }
         }
 
         frontier = next_frontier;
     }
 
-    frontier.len()
+    Ok(frontier.len().into())
 }
 
-pub fn part_two(input: &str) -> usize {
-    let (grid, (sy, sx)) = parse_grid(input);
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    let Input { ref grid, starting_pos: (sy, sx) } = *input;
 
     let mut frontier = HashSet::default();
     frontier.insert((sy as isize, sx as isize));
@@ -90,7 +78,8 @@ pub fn part_two(input: &str) -> usize {
         let mut next_frontier = HashSet::default();
 
         for (y, x) in frontier {
-            for (dy, dx) in &[(0, 1), (0, -1), (1, 0), (-1, 0)] {
+            for direction in Direction4::ALL {
+                let (dy, dx) = direction.delta();
                 let (ny, nx) = (y + dy, x + dx);
 
                 let (iy, ix) = (
@@ -113,11 +102,14 @@ pub fn part_two(input: &str) -> usize {
         }
     }
 
+    if y.len() < 3 {
+        return Err(SolveError("the garden has to be wider than 65 plots".to_string()));
+    }
+
     let x = (26501365 - 65) / grid.cols();
     let y = interpolate(y[0] as f64, y[1] as f64, y[2] as f64, x as f64);
 
-    y as usize
-    
+    Ok((y as usize).into())
 }
 
 
@@ -137,7 +129,7 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let input = read_file("inputs", 21);
-        assert_eq!(part_one(&input), 3666);
+        let input = parse(&read_file("inputs", 21)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 3666);
     }
 }
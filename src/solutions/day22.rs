@@ -3,29 +3,49 @@ use std::cmp::max;
 use itertools::Itertools;
 use rustc_hash::FxHashMap as HashMap;
 
+use crate::parse::{number, split_once};
+use crate::{Answer, ParseError, SolveError};
+
 type Coord = (usize, usize, usize);
 
-fn parse_cube(line: &str) -> Brick {
-    let (start, end) = line.split_once('~').expect("cant parse cube");
+fn parse_cube(input: &str, line: &str) -> Result<Brick, ParseError> {
+    let (start, end) = split_once(input, line, "~")?;
 
-    let parse_coord = |s: &str| {
+    let parse_coord = |s: &str| -> Result<Coord, ParseError> {
         let mut parts = s.split(',');
 
-        let mut parse_digit = || {
-            parts
-                .next()
-                .expect("cant parse coord")
-                .parse::<usize>()
-                .expect("cant parse coord")
+        let mut parse_digit = || match parts.next() {
+            Some(part) => number(input, part),
+            None => Err(ParseError::at(input, s, "expected three coordinates in")),
         };
 
-        (parse_digit(), parse_digit(), parse_digit())
+        Ok((parse_digit()?, parse_digit()?, parse_digit()?))
+    };
+
+    let brick = Brick {
+        start: parse_coord(start)?,
+        end: parse_coord(end)?,
     };
 
-    Brick {
-        start: parse_coord(start),
-        end: parse_coord(end),
+    let (start, end) = (brick.start, brick.end);
+
+    if start.0 > end.0 || start.1 > end.1 || start.2 > end.2 {
+        return Err(ParseError::at(
+            input,
+            line,
+            "brick ends before it starts in",
+        ));
     }
+
+    if start.2 == 0 {
+        return Err(ParseError::at(
+            input,
+            line,
+            "brick is in the ground, z starts at 1 in",
+        ));
+    }
+
+    Ok(brick)
 }
 
 #[derive(Debug, Clone)]
@@ -48,7 +68,7 @@ type CubeIdx = usize;
 type ColumnPosition = usize;
 
 #[derive(Debug, Clone)]
-struct Universe {
+pub struct Universe {
     bricks: Vec<Brick>,
     column_idxs: HashMap<CubeIdx, Vec<(ColumnIdx, ColumnPosition)>>,
     column_vectors: HashMap<ColumnIdx, Vec<CubeIdx>>,
@@ -120,11 +140,9 @@ impl Universe {
                     continue;
                 }
 
+                // `parse` rejects overlapping bricks, so the next one in the
+                // column is always below this one.
                 let other_cube = &self.bricks[*other_cube_idx];
-
-                assert!(brick.start.2 <= brick.end.2);
-                assert!(other_cube.start.2 < brick.start.2);
-
                 let distance = brick.start.2 - other_cube.end.2 - 1;
 
                 if min_distance.is_none() || distance < min_distance.unwrap() {
@@ -218,43 +236,82 @@ impl Universe {
     }
 }
 
-pub fn part_one(input: &str) -> usize {
-    let bricks = input.lines().map(parse_cube).collect::<Vec<_>>();
-    let mut universe = Universe::new(bricks);
+/// The bricks after they have all fallen down, both parts start from there.
+pub type Input = Universe;
 
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let mut bricks = Vec::new();
+    let mut occupied = HashMap::default();
+
+    for (idx, line) in input.lines().enumerate() {
+        let brick = parse_cube(input, line)?;
+
+        for coord in brick.iter() {
+            if let Some(other) = occupied.insert(coord, idx) {
+                let message = format!("brick overlaps the brick on line {} in", other + 1);
+                return Err(ParseError::at(input, line, message));
+            }
+        }
+
+        bricks.push(brick);
+    }
+
+    let mut universe = Universe::new(bricks);
     universe.settle();
-    universe.non_critical_1()
+
+    Ok(universe)
 }
 
-pub fn part_two(input: &str) -> usize {
-    let bricks = input.lines().map(parse_cube).collect::<Vec<_>>();
-    let mut universe = Universe::new(bricks);
+pub fn part_one(input: &Input) -> Result<Answer, SolveError> {
+    Ok(input.non_critical_1().into())
+}
 
-    universe.settle();
-    universe.non_critical_2()
+pub fn part_two(input: &Input) -> Result<Answer, SolveError> {
+    Ok(input.non_critical_2().into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::{read_example, read_file};
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 22);
-        assert_eq!(part_one(&input), 5);
+        let input = parse(&read_example(22, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 5);
 
-        let input = read_file("inputs", 22);
-        assert_eq!(part_one(&input), 465);
+        let input = parse(&read_file("inputs", 22)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 465);
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 22);
-        assert_eq!(part_two(&input), 7);
+        let input = parse(&read_example(22, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 7);
 
-        let input = read_file("inputs", 22);
-        assert_eq!(part_two(&input), 79042);
+        let input = parse(&read_file("inputs", 22)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 79042);
+    }
+
+    #[test]
+    fn test_invalid_cube() {
+        let err = parse("1,0,1~1,2,1\n0,0,2~2,0\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 7, "2,0"));
+
+        let err = parse("1,0,1~1,2,1\n0,2,3~0,0,3\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (2, "brick ends before it starts in")
+        );
+
+        let err = parse("1,0,0~1,2,0\n").unwrap_err();
+        assert_eq!(err.line, 1);
+
+        let err = parse("1,0,1~1,2,1\n0,1,1~2,1,1\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (2, "brick overlaps the brick on line 1 in")
+        );
     }
 }
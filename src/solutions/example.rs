@@ -1,27 +1,35 @@
-pub fn part_one(input: &str) -> u32 {
-    0
+use crate::{Answer, ParseError, SolveError};
+
+pub type Input = Vec<String>;
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    Ok(input.lines().map(str::to_string).collect())
 }
 
-pub fn part_two(input: &str) -> u32 {
-    0
+pub fn part_one(_input: &Input) -> Result<Answer, SolveError> {
+    Ok(0.into())
+}
+
+pub fn part_two(_input: &Input) -> Result<Answer, SolveError> {
+    Ok(0.into())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::read_file;
+    use crate::read_example;
 
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let input = read_file("examples", 25);
-        assert_eq!(part_one(&input), 0);
+        let input = parse(&read_example(25, "example")).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 0);
 
     }
 
     #[test]
     fn test_part_two() {
-        let input = read_file("examples", 25);
-        assert_eq!(part_two(&input), 0);
+        let input = parse(&read_example(25, "example")).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 0);
     }
-}
+}
\ No newline at end of file
@@ -1,22 +1,152 @@
-pub mod day01;
-pub mod day02;
-pub mod day03;
-pub mod day04;
-pub mod day05;
-pub mod day06;
-pub mod day07;
-pub mod day08;
-pub mod day09;
-pub mod day10;
-pub mod day11;
-pub mod day12;
-pub mod day13;
-pub mod day14;
-pub mod day15;
-pub mod day16;
-pub mod day17;
-pub mod day18;
-pub mod day19;
-pub mod day20;
-pub mod day21;
-pub mod day22;
\ No newline at end of file
+use crate::{Answer, ParseError, Parsed, SolveError, Solution};
+
+fn downcast<T: 'static>(day: u8, input: &Parsed) -> &T {
+    input
+        .downcast_ref()
+        .unwrap_or_else(|| panic!("input of day {} was parsed by another day", day))
+}
+
+macro_rules! solutions {
+    ($($module:ident => $name:ident, $day:literal, $title:literal;)*) => {
+        $(
+            pub mod $module;
+
+            pub struct $name;
+
+            impl Solution for $name {
+                fn day(&self) -> u8 {
+                    $day
+                }
+
+                fn title(&self) -> &'static str {
+                    $title
+                }
+
+                fn parse(&self, input: &str) -> Result<Parsed, ParseError> {
+                    Ok(Box::new($module::parse(input)?))
+                }
+
+                fn part_one(&self, input: &Parsed) -> Result<Answer, SolveError> {
+                    $module::part_one(downcast(self.day(), input))
+                }
+
+                fn part_two(&self, input: &Parsed) -> Result<Answer, SolveError> {
+                    $module::part_two(downcast(self.day(), input))
+                }
+            }
+        )*
+
+        /// All solved days, ordered by day number.
+        pub static SOLUTIONS: &[&dyn Solution] = &[$(&$name),*];
+    };
+}
+
+solutions! {
+    day01 => Day01, 1, "Trebuchet?!";
+    day02 => Day02, 2, "Cube Conundrum";
+    day03 => Day03, 3, "Gear Ratios";
+    day04 => Day04, 4, "Scratchcards";
+    day05 => Day05, 5, "If You Give A Seed A Fertilizer";
+    day06 => Day06, 6, "Wait For It";
+    day07 => Day07, 7, "Camel Cards";
+    day08 => Day08, 8, "Haunted Wasteland";
+    day09 => Day09, 9, "Mirage Maintenance";
+    day10 => Day10, 10, "Pipe Maze";
+    day11 => Day11, 11, "Cosmic Expansion";
+    day12 => Day12, 12, "Hot Springs";
+    day13 => Day13, 13, "Point of Incidence";
+    day14 => Day14, 14, "Parabolic Reflector Dish";
+    day15 => Day15, 15, "Lens Library";
+    day16 => Day16, 16, "The Floor Will Be Lava";
+    day17 => Day17, 17, "Clumsy Crucible";
+    day18 => Day18, 18, "Lavaduct Lagoon";
+    day19 => Day19, 19, "Aplenty";
+    day20 => Day20, 20, "Pulse Propagation";
+    day21 => Day21, 21, "Step Counter";
+    day22 => Day22, 22, "Sand Slabs";
+}
+
+pub fn find(day: u8) -> Option<&'static dyn Solution> {
+    SOLUTIONS.iter().copied().find(|solution| solution.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input;
+
+    use super::*;
+
+    #[test]
+    fn test_registry_is_ordered() {
+        for [a, b] in SOLUTIONS.array_windows() {
+            assert!(a.day() < b.day(), "day {} registered out of order", b.day());
+        }
+    }
+
+    /// Runs every example in `examples/dayNN/` against the registered day and
+    /// compares the answers with the ones in the `.toml` next to it.
+    #[test]
+    fn test_examples() {
+        let mut failures = Vec::new();
+        let mut checked = 0;
+
+        for solution in SOLUTIONS {
+            let day = solution.day();
+
+            let examples = match input::examples(day) {
+                Ok(examples) => examples,
+                Err(err) => {
+                    failures.push(err.to_string());
+                    continue;
+                }
+            };
+
+            for example in examples {
+                let input = match solution.parse(&example.input) {
+                    Ok(input) => input,
+                    Err(err) => {
+                        failures.push(format!("day {} {}: {}", day, example.name, err));
+                        continue;
+                    }
+                };
+
+                for part in [1, 2] {
+                    let Some(expected) = example.expected.get(day, part) else {
+                        continue;
+                    };
+
+                    let answer = match part {
+                        1 => solution.part_one(&input),
+                        _ => solution.part_two(&input),
+                    };
+
+                    checked += 1;
+
+                    match answer {
+                        Ok(answer) if answer == *expected => {}
+                        Ok(answer) => failures.push(format!(
+                            "day {} {} part {}: expected {}, got {}",
+                            day, example.name, part, expected, answer
+                        )),
+                        Err(err) => failures.push(format!(
+                            "day {} {} part {}: {}",
+                            day, example.name, part, err
+                        )),
+                    }
+                }
+            }
+        }
+
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+        assert!(checked > 0);
+    }
+
+    #[test]
+    fn test_find() {
+        for solution in SOLUTIONS {
+            assert_eq!(find(solution.day()).unwrap().day(), solution.day());
+        }
+
+        assert!(find(0).is_none());
+    }
+}
@@ -0,0 +1,151 @@
+use std::{fmt, time::Duration};
+
+/// How long one day took, split into loading and parsing the input and the
+/// two parts.
+///
+/// Parts that were not run are `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayTimings {
+    pub day: u8,
+    pub title: &'static str,
+    pub load: Duration,
+    pub parse: Duration,
+    pub parts: [Option<Duration>; 2],
+}
+
+impl DayTimings {
+    pub fn total(&self) -> Duration {
+        self.load + self.parse + self.parts.iter().flatten().sum::<Duration>()
+    }
+}
+
+/// The timings of several days, slowest first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    days: Vec<DayTimings>,
+}
+
+impl Report {
+    pub fn new(mut days: Vec<DayTimings>) -> Self {
+        days.sort_by(|a, b| b.total().cmp(&a.total()).then(a.day.cmp(&b.day)));
+        Self { days }
+    }
+
+    pub fn days(&self) -> &[DayTimings] {
+        &self.days
+    }
+
+    pub fn total(&self) -> Duration {
+        self.days.iter().map(DayTimings::total).sum()
+    }
+
+    /// The share of the total runtime spent on `day`, in percent.
+    pub fn share(&self, day: &DayTimings) -> f64 {
+        let total = self.total().as_secs_f64();
+
+        if total == 0.0 {
+            0.0
+        } else {
+            day.total().as_secs_f64() / total * 100.0
+        }
+    }
+
+    pub fn slowest(&self, n: usize) -> &[DayTimings] {
+        &self.days[..n.min(self.days.len())]
+    }
+}
+
+fn column(duration: Option<Duration>) -> String {
+    duration.map_or_else(|| "-".to_string(), |duration| format!("{:.2?}", duration))
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.days.iter().map(|day| day.title.len()).max().unwrap_or(0).max(5);
+
+        writeln!(
+            f,
+            "{:>3}  {:<width$}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {:>6}",
+            "Day", "Title", "Load", "Parse", "Part 1", "Part 2", "Total", "Share"
+        )?;
+
+        for day in &self.days {
+            writeln!(
+                f,
+                "{:>3}  {:<width$}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {:>5.1}%",
+                day.day,
+                day.title,
+                column(Some(day.load)),
+                column(Some(day.parse)),
+                column(day.parts[0]),
+                column(day.parts[1]),
+                column(Some(day.total())),
+                self.share(day),
+            )?;
+        }
+
+        writeln!(
+            f,
+            "{:>3}  {:<width$}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {:>5.1}%",
+            "",
+            "Total",
+            column(Some(self.days.iter().map(|day| day.load).sum())),
+            column(Some(self.days.iter().map(|day| day.parse).sum())),
+            column(Some(self.days.iter().filter_map(|day| day.parts[0]).sum())),
+            column(Some(self.days.iter().filter_map(|day| day.parts[1]).sum())),
+            column(Some(self.total())),
+            if self.days.is_empty() { 0.0 } else { 100.0 },
+        )?;
+
+        let slowest: Vec<_> = self
+            .slowest(3)
+            .iter()
+            .map(|day| format!("day {} ({:.1}%)", day.day, self.share(day)))
+            .collect();
+
+        write!(f, "\nSlowest: {}", slowest.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(day: u8, load: u64, parse: u64, one: u64, two: Option<u64>) -> DayTimings {
+        DayTimings {
+            day,
+            title: "Test",
+            load: Duration::from_millis(load),
+            parse: Duration::from_millis(parse),
+            parts: [Some(Duration::from_millis(one)), two.map(Duration::from_millis)],
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let report = Report::new(vec![
+            day(1, 1, 0, 4, Some(5)),
+            day(2, 0, 10, 20, Some(50)),
+            day(3, 1, 0, 9, None),
+        ]);
+
+        let order: Vec<_> = report.days().iter().map(|day| day.day).collect();
+        assert_eq!(order, [2, 1, 3]);
+
+        assert_eq!(report.total(), Duration::from_millis(100));
+        assert_eq!(report.share(&report.days()[0]), 80.0);
+        assert_eq!(report.slowest(2).len(), 2);
+        assert_eq!(report.slowest(5).len(), 3);
+    }
+
+    #[test]
+    fn test_display() {
+        let report = Report::new(vec![day(1, 1, 2, 2, Some(5)), day(3, 1, 0, 9, None)]);
+        let table = report.to_string();
+        let lines: Vec<_> = table.lines().collect();
+
+        assert_eq!(lines[2], "  3  Test       1.00ms      0.00ns      9.00ms           -     10.00ms   50.0%");
+        assert_eq!(lines[3], "     Total      2.00ms      2.00ms     11.00ms      5.00ms     20.00ms  100.0%");
+        assert_eq!(lines[5], "Slowest: day 1 (50.0%), day 3 (50.0%)");
+    }
+}